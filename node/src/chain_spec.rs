@@ -141,6 +141,7 @@ fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 		}),
 		stablecoin: Some(StablecoinConfig {
 			shareholders: endowed_accounts.iter().cloned().map(|acc| (acc, 1)).collect(),
+			balances: vec![],
 		}),
 	}
 }
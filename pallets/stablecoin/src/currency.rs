@@ -0,0 +1,422 @@
+//! # Currency
+//!
+//! Implementation of the `Currency` trait to allow using the stablecoin with pallets that expect
+//! a `frame_support::traits::Currency`.
+//!
+//! Coins created or destroyed through the `Currency` interface are accounted for with imbalances
+//! which update `CoinSupply` when they are dropped. This keeps the coin supply consistent with the
+//! balances: `deposit_creating` increases the supply unless the resulting `PositiveImbalance` is
+//! offset against a `NegativeImbalance` (e.g., from `withdraw`) and vice versa.
+//!
+//! `withdraw` and `burn` do not reduce the coin supply below `MinimumSupply`. Slashing and
+//! `make_free_balance_be` are exempt because they cannot fail, so dropping their imbalances
+//! can reduce the supply below it.
+//!
+//! Note: The seigniorage mechanics (bids and bonds) are not exposed through this interface.
+
+use super::*;
+use frame_support::traits::{
+	Currency, ExistenceRequirement, Imbalance, SignedImbalance, TryDrop, WithdrawReasons,
+};
+use sp_std::mem;
+
+/// Opaque, move-only struct with private fields that serves as a token denoting that
+/// Coins have been created without any equal and opposite accounting.
+///
+/// Increases the coin supply by its amount when dropped.
+#[must_use]
+pub struct PositiveImbalance<T: Trait>(Coins, PhantomData<T>);
+
+impl<T: Trait> PositiveImbalance<T> {
+	/// Create a new positive imbalance from `amount`.
+	pub fn new(amount: Coins) -> Self {
+		PositiveImbalance(amount, PhantomData)
+	}
+}
+
+/// Opaque, move-only struct with private fields that serves as a token denoting that
+/// Coins have been destroyed without any equal and opposite accounting.
+///
+/// Decreases the coin supply by its amount when dropped.
+#[must_use]
+pub struct NegativeImbalance<T: Trait>(Coins, PhantomData<T>);
+
+impl<T: Trait> NegativeImbalance<T> {
+	/// Create a new negative imbalance from `amount`.
+	pub fn new(amount: Coins) -> Self {
+		NegativeImbalance(amount, PhantomData)
+	}
+}
+
+impl<T: Trait> TryDrop for PositiveImbalance<T> {
+	fn try_drop(self) -> Result<(), Self> {
+		self.drop_zero()
+	}
+}
+
+impl<T: Trait> Imbalance<Coins> for PositiveImbalance<T> {
+	type Opposite = NegativeImbalance<T>;
+
+	fn zero() -> Self {
+		Self::new(0)
+	}
+
+	fn drop_zero(self) -> Result<(), Self> {
+		if self.0 == 0 {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+
+	fn split(self, amount: Coins) -> (Self, Self) {
+		let first = min(self.0, amount);
+		let second = self.0 - first;
+		mem::forget(self);
+		(Self::new(first), Self::new(second))
+	}
+
+	fn merge(mut self, other: Self) -> Self {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+		self
+	}
+
+	fn subsume(&mut self, other: Self) {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+	}
+
+	fn offset(self, other: Self::Opposite) -> Result<Self, Self::Opposite> {
+		let (a, b) = (self.0, other.0);
+		mem::forget((self, other));
+		if a >= b {
+			Ok(Self::new(a - b))
+		} else {
+			Err(NegativeImbalance::new(b - a))
+		}
+	}
+
+	fn peek(&self) -> Coins {
+		self.0
+	}
+}
+
+impl<T: Trait> TryDrop for NegativeImbalance<T> {
+	fn try_drop(self) -> Result<(), Self> {
+		self.drop_zero()
+	}
+}
+
+impl<T: Trait> Imbalance<Coins> for NegativeImbalance<T> {
+	type Opposite = PositiveImbalance<T>;
+
+	fn zero() -> Self {
+		Self::new(0)
+	}
+
+	fn drop_zero(self) -> Result<(), Self> {
+		if self.0 == 0 {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+
+	fn split(self, amount: Coins) -> (Self, Self) {
+		let first = min(self.0, amount);
+		let second = self.0 - first;
+		mem::forget(self);
+		(Self::new(first), Self::new(second))
+	}
+
+	fn merge(mut self, other: Self) -> Self {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+		self
+	}
+
+	fn subsume(&mut self, other: Self) {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+	}
+
+	fn offset(self, other: Self::Opposite) -> Result<Self, Self::Opposite> {
+		let (a, b) = (self.0, other.0);
+		mem::forget((self, other));
+		if a >= b {
+			Ok(Self::new(a - b))
+		} else {
+			Err(PositiveImbalance::new(b - a))
+		}
+	}
+
+	fn peek(&self) -> Coins {
+		self.0
+	}
+}
+
+impl<T: Trait> Drop for PositiveImbalance<T> {
+	/// Basic drop handler will just square up the coin supply.
+	fn drop(&mut self) {
+		<CoinSupply>::mutate(|supply| *supply = supply.saturating_add(self.0));
+	}
+}
+
+impl<T: Trait> Drop for NegativeImbalance<T> {
+	/// Basic drop handler will just square up the coin supply.
+	///
+	/// Note: Does not respect `MinimumSupply` because dropping cannot fail.
+	fn drop(&mut self) {
+		<CoinSupply>::mutate(|supply| *supply = supply.saturating_sub(self.0));
+	}
+}
+
+impl<T: Trait> Currency<T::AccountId> for Module<T> {
+	type Balance = Coins;
+	type PositiveImbalance = PositiveImbalance<T>;
+	type NegativeImbalance = NegativeImbalance<T>;
+
+	/// Return the total balance of the given account, including the Coins reserved for bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 2 reads from balance storage maps
+	fn total_balance(who: &T::AccountId) -> Coins {
+		Self::get_balance(who).saturating_add(Self::reserved_balance(who))
+	}
+
+	/// Test whether the given account can be slashed with `value`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read from balance storage map
+	fn can_slash(who: &T::AccountId, value: Coins) -> bool {
+		Self::get_balance(who) >= value
+	}
+
+	/// Return the amount of Coins in circulation.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read
+	fn total_issuance() -> Coins {
+		Self::coin_supply()
+	}
+
+	/// Return the `ExistentialDeposit`.
+	fn minimum_balance() -> Coins {
+		T::ExistentialDeposit::get()
+	}
+
+	/// Reduce the coin supply by `amount` (saturating at `MinimumSupply`) and return the opposite imbalance.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write of the coin supply
+	fn burn(mut amount: Coins) -> Self::PositiveImbalance {
+		<CoinSupply>::mutate(|supply| {
+			amount = min(amount, supply.saturating_sub(T::MinimumSupply::get()));
+			*supply -= amount;
+		});
+		PositiveImbalance::new(amount)
+	}
+
+	/// Increase the coin supply by `amount` (saturating at the maximum) and return the opposite imbalance.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write of the coin supply
+	fn issue(mut amount: Coins) -> Self::NegativeImbalance {
+		<CoinSupply>::mutate(|supply| {
+			amount = min(amount, Coins::max_value() - *supply);
+			*supply += amount;
+		});
+		NegativeImbalance::new(amount)
+	}
+
+	/// Return the free balance of the given account, excluding the Coins reserved for bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read from balance storage map
+	fn free_balance(who: &T::AccountId) -> Coins {
+		Self::get_balance(who)
+	}
+
+	/// Stablecoin balances cannot be locked, so any withdrawal is allowed unless the pallet is paused
+	/// or `who` is frozen.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 2 reads
+	fn ensure_can_withdraw(
+		who: &T::AccountId,
+		_amount: Coins,
+		_reasons: WithdrawReasons,
+		_new_balance: Coins,
+	) -> DispatchResult {
+		ensure!(!Self::paused(), Error::<T>::Paused);
+		Self::ensure_not_frozen(who)
+	}
+
+	/// Transfer `value` Coins from `source` to `dest`.
+	///
+	/// Fails with `BelowExistentialDeposit` if `existence_requirement` is `KeepAlive` and the
+	/// transfer would empty the account of `source` and with `Paused` if the pallet is paused.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 2 reads and write from and to balance storage map
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		value: Coins,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		ensure!(!Self::paused(), Error::<T>::Paused);
+		Self::ensure_keep_alive(source, value, existence_requirement)?;
+		Self::transfer_from_to(source, dest, value)
+	}
+
+	/// Slash up to `value` Coins from `who` and return the slashed imbalance together with the
+	/// amount that could not be slashed.
+	///
+	/// Not affected by pausing the pallet, freezing `who` or `MinimumSupply` because slashing
+	/// cannot fail and penalties should still apply.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write of balance storage map
+	fn slash(who: &T::AccountId, value: Coins) -> (Self::NegativeImbalance, Coins) {
+		let balance = Self::get_balance(who);
+		let slashed = min(balance, value);
+		Self::set_balance(who, balance - slashed);
+		(NegativeImbalance::new(slashed), value - slashed)
+	}
+
+	/// Add `value` Coins to the balance of the existing account `who`.
+	///
+	/// Fails with `DeadAccount` if `who` has no balance, with `Paused` if the pallet is paused
+	/// and with `AccountFrozen` if `who` is frozen.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write of balance storage map
+	fn deposit_into_existing(
+		who: &T::AccountId,
+		value: Coins,
+	) -> Result<Self::PositiveImbalance, DispatchError> {
+		ensure!(!Self::paused(), Error::<T>::Paused);
+		Self::ensure_not_frozen(who)?;
+		ensure!(<Balance<T>>::contains_key(who), Error::<T>::DeadAccount);
+		let updated = Self::get_balance(who)
+			.checked_add(value)
+			.ok_or(Error::<T>::BalanceOverflow)?;
+		// ↑ verify ↑
+		// ↓ update ↓
+		Self::set_balance(who, updated);
+		Ok(PositiveImbalance::new(value))
+	}
+
+	/// Add `value` Coins to the balance of `who`, creating the account if necessary.
+	///
+	/// Does nothing if the pallet is paused, if `who` is frozen, if a new account would be created
+	/// with less than `ExistentialDeposit` or if the balance would overflow.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write of balance storage map
+	fn deposit_creating(who: &T::AccountId, value: Coins) -> Self::PositiveImbalance {
+		if Self::paused() || Self::is_frozen(who) {
+			return PositiveImbalance::zero();
+		}
+		let balance = Self::get_balance(who);
+		if balance == 0 && value < T::ExistentialDeposit::get() {
+			return PositiveImbalance::zero();
+		}
+		match balance.checked_add(value) {
+			Some(updated) => {
+				Self::set_balance(who, updated);
+				PositiveImbalance::new(value)
+			}
+			None => PositiveImbalance::zero(),
+		}
+	}
+
+	/// Remove `value` Coins from the balance of `who`.
+	///
+	/// Fails with `InsufficientBalance` if `who` does not have enough Coins, with
+	/// `BelowExistentialDeposit` if the remaining balance would be too low, with `Paused`
+	/// if the pallet is paused, with `AccountFrozen` if `who` is frozen and with
+	/// `CoinSupplyUnderflow` if dropping the returned imbalance would reduce the coin supply
+	/// below `MinimumSupply`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read of the coin supply, 1 read and write of balance storage map
+	fn withdraw(
+		who: &T::AccountId,
+		value: Coins,
+		_reasons: WithdrawReasons,
+		liveness: ExistenceRequirement,
+	) -> Result<Self::NegativeImbalance, DispatchError> {
+		ensure!(!Self::paused(), Error::<T>::Paused);
+		Self::ensure_not_frozen(who)?;
+		let remaining_supply = Self::coin_supply()
+			.checked_sub(value)
+			.ok_or(Error::<T>::CoinSupplyUnderflow)?;
+		ensure!(remaining_supply >= T::MinimumSupply::get(), Error::<T>::CoinSupplyUnderflow);
+		let remaining = Self::get_balance(who)
+			.checked_sub(value)
+			.ok_or(Error::<T>::InsufficientBalance)?;
+		ensure!(
+			remaining == 0 || remaining >= T::ExistentialDeposit::get(),
+			Error::<T>::BelowExistentialDeposit
+		);
+		Self::ensure_keep_alive(who, value, liveness)?;
+		// ↑ verify ↑
+		// ↓ update ↓
+		Self::set_balance(who, remaining);
+		Ok(NegativeImbalance::new(value))
+	}
+
+	/// Set the balance of `who` to `balance` and return the resulting imbalance.
+	///
+	/// Does nothing if `balance` is nonzero but below `ExistentialDeposit`.
+	/// Not affected by pausing the pallet, freezing `who` or `MinimumSupply` because it is meant
+	/// for privileged callers (e.g., genesis and governance) only.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write of balance storage map
+	fn make_free_balance_be(
+		who: &T::AccountId,
+		balance: Coins,
+	) -> SignedImbalance<Coins, Self::PositiveImbalance> {
+		if balance != 0 && balance < T::ExistentialDeposit::get() {
+			return SignedImbalance::Positive(PositiveImbalance::zero());
+		}
+		let previous = Self::get_balance(who);
+		Self::set_balance(who, balance);
+		if balance >= previous {
+			SignedImbalance::Positive(PositiveImbalance::new(balance - previous))
+		} else {
+			SignedImbalance::Negative(NegativeImbalance::new(previous - balance))
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Ensure that removing `value` Coins from `who` does not remove the account if it should be kept alive.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read from balance storage map
+	fn ensure_keep_alive(who: &T::AccountId, value: Coins, requirement: ExistenceRequirement) -> DispatchResult {
+		if requirement == ExistenceRequirement::KeepAlive && T::ExistentialDeposit::get() > 0 {
+			ensure!(Self::get_balance(who) != value, Error::<T>::BelowExistentialDeposit);
+		}
+		Ok(())
+	}
+}
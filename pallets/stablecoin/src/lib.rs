@@ -35,16 +35,35 @@
 //! Here is an example imlementation of its trait:
 //!
 //! ```rust,ignore
-//! use pallet_stablecoin::Coins;
+//! use pallet_stablecoin::{BidEvictionPolicy, BondIndex, BondPayoutMode, Coins, FixedExpiration};
 //!
 //! parameter_types! {
 //!     pub const ExpirationPeriod: BlockNumber = 5 * 365 * DAYS; // 5 years = 5 * 365 * DAYS
+//!     pub const BidExpirationPeriod: BlockNumber = 30 * DAYS;
 //!     pub const MaximumBids: usize = 1_000;
+//!     pub const MaxTransfersPerBatch: u32 = 100;
+//!     pub const MaxBidsPerAccount: u64 = 100;
+//!     pub const MaxBidActionsPerBlock: u32 = 10;
+//!     pub const EvictionPolicy: BidEvictionPolicy = BidEvictionPolicy::EvictLowest;
+//!     pub const MaximumBonds: BondIndex = 10_000;
+//!     pub const PayoutMode: BondPayoutMode = BondPayoutMode::Fifo;
+//!     pub const MaxBondPayoutPerExpansion: Coins = Coins::max_value();
 //!     pub const MinimumBondPrice: Perbill = Perbill::from_percent(10);
+//!     pub const BondBonus: Perbill = Perbill::zero();
 //!     pub const AdjustmentFrequency: BlockNumber = 1 * MINUTES; // 1 minute = 60000 / MILLISECS_PER_BLOCK
+//!     pub const AdjustmentDamping: Perbill = Perbill::from_percent(100);
+//!     pub const PegTolerance: Perbill = Perbill::from_percent(1);
+//!     pub const ExpansionFactor: Perbill = Perbill::from_percent(100);
+//!     pub const ContractionFactor: Perbill = Perbill::from_percent(100);
 //!     pub const BaseUnit: Coins = 1_000_000;
+//!     pub const MinimumBidQuantity: Coins = BaseUnit::get();
+//!     pub const MinPriceBound: Coins = BaseUnit::get() / 100;
+//!     pub const MaxPriceBound: Coins = 100 * BaseUnit::get();
+//!     pub const Symbol: &'static [u8] = b"COIN";
 //!     pub const InitialSupply: Coins = 1000 * BaseUnit::get();
 //!     pub const MinimumSupply: Coins = BaseUnit::get();
+//!     pub const MaximumBondExpirationsPerBlock: u32 = 100;
+//!     pub const ExistentialDeposit: Coins = 0;
 //! }
 //!
 //! impl pallet_stablecoin::Trait for Runtime {
@@ -52,12 +71,34 @@
 //!     
 //!     type CoinPrice = some_price_oracle::Module<Runtime>;
 //!     type ExpirationPeriod = ExpirationPeriod;
+//!     type BondExpiration = FixedExpiration<ExpirationPeriod>;
+//!     type BidExpirationPeriod = BidExpirationPeriod;
+//!     type AdminOrigin = system::EnsureRoot<AccountId>;
 //!     type MaximumBids = MaximumBids;
+//!     type MaxTransfersPerBatch = MaxTransfersPerBatch;
+//!     type MaxBidsPerAccount = MaxBidsPerAccount;
+//!     type MaxBidActionsPerBlock = MaxBidActionsPerBlock;
+//!     type BidEvictionPolicy = EvictionPolicy;
+//!     type MaximumBonds = MaximumBonds;
+//!     type BondPayoutMode = PayoutMode;
+//!     type MaxBondPayoutPerExpansion = MaxBondPayoutPerExpansion;
 //!     type MinimumBondPrice = MinimumBondPrice;
+//!     type BondBonus = BondBonus;
 //!     type AdjustmentFrequency = AdjustmentFrequency;
+//!     type AdjustmentDamping = AdjustmentDamping;
+//!     type PegTolerance = PegTolerance;
+//!     type ExpansionFactor = ExpansionFactor;
+//!     type ContractionFactor = ContractionFactor;
 //!     type BaseUnit = BaseUnit;
+//!     type MinimumBidQuantity = MinimumBidQuantity;
+//!     type MinPriceBound = MinPriceBound;
+//!     type MaxPriceBound = MaxPriceBound;
+//!     type Symbol = Symbol;
 //!     type InitialSupply = InitialSupply;
 //!     type MinimumSupply = MinimumSupply;
+//!     type MaximumBondExpirationsPerBlock = MaximumBondExpirationsPerBlock;
+//!     type ExistentialDeposit = ExistentialDeposit;
+//!     type OnSupplyChange = ();
 //! }
 //! ```
 //!
@@ -95,24 +136,34 @@ use sp_std::prelude::*;
 
 use adapters::{BoundedPriorityQueue, BoundedDeque};
 use codec::{Decode, Encode};
-use core::cmp::{max, min, Ord, Ordering};
+use core::cmp::{min, Ord, Ordering, Reverse};
+use core::convert::TryFrom;
+use core::iter;
+use core::marker::PhantomData;
+use core::ops::Add;
 use fixed::{types::extra::U64, FixedU128};
 use frame_support::{
 	debug::native,
 	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::{DispatchError, DispatchResult},
 	ensure,
-	traits::Get,
+	storage::unhashed,
+	traits::{EnsureOrigin, Get},
 };
-use num_rational::Ratio;
 use orml_traits::BasicCurrency;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_io::hashing::twox_128;
 use sp_runtime::{
-	traits::{CheckedMul, Zero},
+	traits::{Saturating, Zero},
 	PerThing, Perbill, RuntimeDebug,
 };
-use sp_std::collections::vec_deque::VecDeque;
+use sp_std::collections::{binary_heap::BinaryHeap, vec_deque::VecDeque};
 use system::ensure_signed;
 
+pub mod currency;
+pub mod oracle;
+
 #[cfg(test)]
 mod tests;
 
@@ -120,12 +171,78 @@ mod tests;
 pub trait FetchPrice<Balance> {
 	/// Fetch the current price.
 	fn fetch_price() -> Balance;
+
+	/// Fetch the current price or `None` if the price feed is stale.
+	///
+	/// Supply adjustments are skipped while the feed is stale. Defaults to always returning `fetch_price`.
+	fn fetch_price_checked() -> Option<Balance> {
+		Some(Self::fetch_price())
+	}
+}
+
+/// Strategy to determine when a new bond expires.
+///
+/// Note: `purge_expired_bonds` only checks the front of the bonds queue, so bonds expiring
+/// before the bonds in front of them are only removed once those are.
+pub trait BondExpiration<BlockNumber> {
+	/// Return the expiration block of a bond created at block `now` with `queue_length` bonds in the queue.
+	fn expiration(now: BlockNumber, queue_length: BondIndex) -> BlockNumber;
+}
+
+/// Bonds expire a fixed `Period` of blocks after their creation.
+pub struct FixedExpiration<Period>(PhantomData<Period>);
+
+impl<BlockNumber, Period> BondExpiration<BlockNumber> for FixedExpiration<Period>
+where
+	BlockNumber: Add<Output = BlockNumber>,
+	Period: Get<BlockNumber>,
+{
+	fn expiration(now: BlockNumber, _queue_length: BondIndex) -> BlockNumber {
+		now + Period::get()
+	}
+}
+
+/// Read-only interface to the stablecoin state for other pallets.
+pub trait StablecoinInterface<AccountId> {
+	/// Return the total amount of Coins in circulation.
+	fn total_supply() -> Coins;
+	/// Return the balance of Coins for the account `who`.
+	fn balance_of(who: &AccountId) -> Coins;
+	/// Return the minimum amount of Coins that will be in circulation.
+	fn minimum_supply() -> Coins;
+}
+
+/// Hook for other pallets to react to changes of the coin supply.
+///
+/// Called after the supply change was committed to storage.
+pub trait OnSupplyChange {
+	/// The coin supply was expanded by `amount`.
+	fn on_expand(amount: Coins);
+	/// The coin supply was contracted by `amount`.
+	fn on_contract(amount: Coins);
+}
+
+impl OnSupplyChange for () {
+	fn on_expand(_amount: Coins) {}
+	fn on_contract(_amount: Coins) {}
 }
 
 /// The type used to represent the account balance for the stablecoin.
 pub type Coins = u64;
 /// The type used to index into the map storing the bonds queue.
+///
+/// Indices wrap around, which limits the queue to `BondIndex::max_value()` bonds (see `MaximumBonds`).
 pub type BondIndex = u16;
+/// The type used to index into the map storing the price history of the `MovingAveragePrice` adapter.
+pub type PriceIndex = u16;
+/// The current version of the storage layout, see `StorageVersion`.
+pub const CURRENT_STORAGE_VERSION: u16 = 7;
+
+/// The fixed point type used to represent the fractional deviation of the price from `BaseUnit`.
+///
+/// With 64 integer bits it covers every ratio of two `Coins` values and its 64 fractional bits
+/// make multiplying it with any coin supply lose less than one Coin to rounding.
+pub type Fraction = FixedU128<U64>;
 
 /// The pallet's configuration trait.
 pub trait Trait: system::Trait {
@@ -139,24 +256,141 @@ pub trait Trait: system::Trait {
 	/// The [Basis Whitepaper](https://www.basis.io/basis_whitepaper_en.pdf) recommends an expiration
 	/// period of 5 years.
 	type ExpirationPeriod: Get<<Self as system::Trait>::BlockNumber>;
+	/// The strategy to determine the expiration of new bonds.
+	///
+	/// Use `FixedExpiration<ExpirationPeriod>` for bonds expiring `ExpirationPeriod` blocks after creation.
+	type BondExpiration: BondExpiration<<Self as system::Trait>::BlockNumber>;
+	/// The number of blocks after which a bid is removed from the bidding queue and refunded.
+	///
+	/// Prevents stale bids from locking the Coins of the bidder forever.
+	type BidExpirationPeriod: Get<<Self as system::Trait>::BlockNumber>;
+	/// The origin allowed to call the governance functions, e.g. `EnsureRoot` or a council.
+	type AdminOrigin: EnsureOrigin<Self::Origin>;
 	/// The maximum amount of bids allowed in the queue. Used to prevent the queue from growing forever.
 	type MaximumBids: Get<u64>;
+	/// The maximum amount of transfers in a single `transfer_batch` call.
+	type MaxTransfersPerBatch: Get<u32>;
+	/// The maximum amount of bids a single account can have in the queue.
+	///
+	/// Prevents a single account from filling the queue and evicting the bids of others.
+	type MaxBidsPerAccount: Get<u64>;
+	/// The maximum amount of successful bid-related calls (bidding and cancelling) per account and block.
+	///
+	/// Prevents a single account from churning the queue by repeatedly bidding and cancelling.
+	type MaxBidActionsPerBlock: Get<u32>;
+	/// What happens to new bids if the bidding queue is full, see `BidEvictionPolicy`.
+	type BidEvictionPolicy: Get<BidEvictionPolicy>;
+	/// How supply expansions are distributed among bonds, see `BondPayoutMode`.
+	type BondPayoutMode: Get<BondPayoutMode>;
+	/// The maximum amount of Coins paid to a single bond in one supply expansion.
+	///
+	/// Prevents a single large bond from consuming whole expansions. Use `Coins::max_value()` to disable.
+	/// Coins that cannot be paid to bonds because of the cap are not minted while bonds are outstanding.
+	type MaxBondPayoutPerExpansion: Get<Coins>;
+	/// The maximum amount of bonds allowed in the queue. Used to prevent the queue from growing forever.
+	///
+	/// Contraction stops converting bids to bonds once the queue is full and refunds the bids
+	/// that would have covered the rest of the contraction.
+	type MaximumBonds: Get<BondIndex>;
 	/// The minimum percentage to pay for a bond.
 	///
 	/// The [Basis Whitepaper](https://www.basis.io/basis_whitepaper_en.pdf) recommends a minimum
 	/// bond price of 10% based on simulations.
+	///
+	/// Can be overridden at runtime via `set_minimum_bond_price`.
 	type MinimumBondPrice: Get<Perbill>;
+	/// The bonus added to the payout of new bonds as a fraction of the bid quantity.
+	///
+	/// E.g., a bonus of 5% turns a bid for 100 Coins into a bond paying out 105 Coins.
+	/// The bonus is minted when the bond is paid out. `Perbill::zero()` pays out the bid quantity.
+	type BondBonus: Get<Perbill>;
 	/// The frequency of adjustments of the coin supply.
 	type AdjustmentFrequency: Get<<Self as system::Trait>::BlockNumber>;
+	/// The share of the price deviation corrected per adjustment.
+	///
+	/// `Perbill::from_percent(100)` corrects the full deviation, lower values converge to the peg
+	/// over several adjustments to avoid overshooting.
+	type AdjustmentDamping: Get<Perbill>;
+	/// The relative deviation from `BaseUnit` within which the price is considered at peg.
+	///
+	/// Prices within `BaseUnit * (1 ± PegTolerance)` do not trigger a supply adjustment.
+	/// `Perbill::zero()` adjusts on any deviation.
+	type PegTolerance: Get<Perbill>;
+	/// Scales supply expansions (on top of `AdjustmentDamping`).
+	///
+	/// Together with `ContractionFactor` this allows reacting differently to prices below and
+	/// above the peg. `Perbill::from_percent(100)` for both keeps the adjustment symmetric.
+	type ExpansionFactor: Get<Perbill>;
+	/// Scales supply contractions (on top of `AdjustmentDamping`), see `ExpansionFactor`.
+	type ContractionFactor: Get<Perbill>;
 	/// The amount of Coins that are meant to track the value. Example: A value of 1_000 when tracking
 	/// Dollars means that the Stablecoin will try to maintain a price of 1_000 Coins for 1$.
+	///
+	/// Must be greater than zero.
 	type BaseUnit: Get<Coins>;
+	/// The minimum quantity of a bid for a bond (in Coins), e.g. `BaseUnit`.
+	type MinimumBidQuantity: Get<Coins>;
+	/// The lowest price considered plausible. Lower prices are ignored like a stale price feed.
+	type MinPriceBound: Get<Coins>;
+	/// The highest price considered plausible. Higher prices are ignored like a stale price feed.
+	type MaxPriceBound: Get<Coins>;
+	/// The ticker symbol of the Coins (e.g., `b"COIN"`) for displaying balances.
+	type Symbol: Get<&'static [u8]>;
 	/// The initial supply of Coins.
 	type InitialSupply: Get<Coins>;
 	/// The minimum amount of Coins in circulation.
 	///
-	/// Must be lower than `InitialSupply`.
+	/// Must be at least `BaseUnit` and lower than `InitialSupply`.
 	type MinimumSupply: Get<Coins>;
+	/// The maximum amount of expired bonds removed from the bonds queue in `on_initialize`.
+	/// Used to limit the work done per block.
+	type MaximumBondExpirationsPerBlock: Get<u32>;
+	/// The minimum balance an account needs to keep after sending Coins, unless it sends all of them.
+	///
+	/// A value of 0 allows any transfer.
+	type ExistentialDeposit: Get<Coins>;
+	/// Handler notified about supply expansions and contractions. Use `()` to ignore them.
+	type OnSupplyChange: OnSupplyChange;
+}
+
+/// The deviation of the coin price from `BaseUnit` (or the target unit set by governance, see `target_unit`).
+#[derive(Clone, Copy, PartialEq, RuntimeDebug)]
+pub enum PriceDeviation {
+	/// The price is above `BaseUnit` by the fraction `price / BaseUnit - 1` --> contract the supply.
+	Above(Fraction),
+	/// The price is below `BaseUnit` by the fraction `BaseUnit / price - 1` --> expand the supply.
+	Below(Fraction),
+	/// The price is equal to `BaseUnit`.
+	AtPeg,
+}
+
+/// The supply adjustment resulting from a coin price, see `simulate_adjustment`.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SupplyAction {
+	/// Expand the supply by the amount of Coins.
+	Expand(Coins),
+	/// Contract the supply by (up to) the amount of Coins.
+	Contract(Coins),
+	/// Leave the supply unchanged.
+	None,
+}
+
+/// Aggregated state of the pallet in a single value, see `state_snapshot`.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StablecoinState {
+	/// The total amount of Coins in existence.
+	pub coin_supply: Coins,
+	/// The supply is never contracted below this amount (see `MinimumSupply`).
+	pub minimum_supply: Coins,
+	/// The number of bids in the bidding queue.
+	pub bid_count: u64,
+	/// The number of bonds in the bonds queue.
+	pub bond_count: u32,
+	/// The minimum percentage to pay for a bond (see `minimum_bond_price`).
+	pub minimum_bond_price: Perbill,
+	/// Whether there are any shares to hand out Coins to during supply expansions.
+	pub has_shares: bool,
 }
 
 /// A bond representing (potential) future payout of Coins.
@@ -165,11 +399,75 @@ pub trait Trait: system::Trait {
 ///
 /// + `account` is the recipient of the bond payout.
 /// + `payout` is the amount of Coins payed out.
+/// + `price` is the price of the bid the bond was created from.
 #[derive(Encode, Decode, Default, Clone, PartialEq, PartialOrd, Eq, Ord, RuntimeDebug)]
 pub struct Bond<AccountId, BlockNumber> {
 	account: AccountId,
 	payout: Coins,
 	expiration: BlockNumber,
+	price: Perbill,
+}
+
+impl<AccountId, BlockNumber> Bond<AccountId, BlockNumber> {
+	/// Create a new bond, e.g. to compare against the bonds returned by `Module::bonds`.
+	///
+	/// ```
+	/// use pallet_stablecoin::Bond;
+	/// use sp_runtime::Perbill;
+	///
+	/// let bond = Bond::new(1u64, 100, 42u32, Perbill::from_percent(80));
+	/// assert_eq!(*bond.account(), 1);
+	/// assert_eq!(bond.payout(), 100);
+	/// assert_eq!(*bond.expiration(), 42);
+	/// assert_eq!(bond.price(), Perbill::from_percent(80));
+	/// ```
+	pub fn new(account: AccountId, payout: Coins, expiration: BlockNumber, price: Perbill) -> Self {
+		Bond {
+			account,
+			payout,
+			expiration,
+			price,
+		}
+	}
+
+	/// Return the recipient of the bond payout.
+	pub fn account(&self) -> &AccountId {
+		&self.account
+	}
+
+	/// Return the amount of Coins payed out.
+	pub fn payout(&self) -> Coins {
+		self.payout
+	}
+
+	/// Return the block at which the bond expires.
+	pub fn expiration(&self) -> &BlockNumber {
+		&self.expiration
+	}
+
+	/// Return the price of the bid the bond was created from.
+	pub fn price(&self) -> Perbill {
+		self.price
+	}
+}
+
+/// How the supply expansion is distributed among bonds.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum BondPayoutMode {
+	/// Pay out bonds strictly in queue order.
+	Fifo,
+	/// Pay out bonds in queue order, but if the expansion does not cover all bonds with the
+	/// frontmost expiration, share it among them proportional to their payouts.
+	ProRata,
+}
+
+/// How a new bid is handled if the bidding queue is full.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum BidEvictionPolicy {
+	/// Accept bids higher than the lowest bid in the queue, evicting (and refunding) the lowest bid.
+	EvictLowest,
+	/// Reject every new bid, keeping the bids already in the queue.
+	RejectNew,
 }
 
 /// A bid for a bond of the stablecoin at a certain price.
@@ -177,35 +475,47 @@ pub struct Bond<AccountId, BlockNumber> {
 /// + `account` is the bidder.
 /// + `price` is a percentage of 1 coin.
 /// + `quantity` is the amount of Coins gained on payout of the corresponding bond.
+/// + `sequence` is the position of the bid in the order in which bids were placed.
+/// + `expiration` is the block at which the bid is removed from the queue and refunded.
+/// + `payment` is the amount of Coins reserved for the bid.
 #[derive(Encode, Decode, Default, Clone, RuntimeDebug)]
-pub struct Bid<AccountId> {
+pub struct Bid<AccountId, BlockNumber> {
 	account: AccountId,
 	price: Perbill,
 	quantity: Coins,
+	sequence: u64,
+	expiration: BlockNumber,
+	payment: Coins,
 }
 
 // Implement `Ord` for `Bid` to get the wanted sorting in the priority queue.
-// TODO: Could this create issues in testing? How to address?
-impl<AccountId> PartialEq for Bid<AccountId> {
+// Note: Bids are compared by their position in the queue, not their content.
+impl<AccountId, BlockNumber> PartialEq for Bid<AccountId, BlockNumber> {
 	fn eq(&self, other: &Self) -> bool {
-		self.price == other.price
+		self.price == other.price && self.sequence == other.sequence
 	}
 }
-impl<AccountId> Eq for Bid<AccountId> {}
+impl<AccountId, BlockNumber> Eq for Bid<AccountId, BlockNumber> {}
 
-impl<AccountId> PartialOrd for Bid<AccountId> {
+impl<AccountId, BlockNumber> PartialOrd for Bid<AccountId, BlockNumber> {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
 		Some(self.cmp(other))
 	}
 }
-/// Sort `Bid`s by price.
-impl<AccountId> Ord for Bid<AccountId> {
+/// Sort `Bid`s by price and place earlier bids above later ones at the same price.
+///
+/// Thus equal-priced bids are converted to bonds first-in-first-out and the latest of them
+/// is evicted first.
+impl<AccountId, BlockNumber> Ord for Bid<AccountId, BlockNumber> {
 	fn cmp(&self, other: &Self) -> Ordering {
-		self.price.cmp(&other.price)
+		self.price
+			.cmp(&other.price)
+			.then_with(|| other.sequence.cmp(&self.sequence))
 	}
 }
 
 /// Error returned from `remove_coins` if there is an over- or underflow.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
 pub enum BidError {
 	/// `remove_coins` overflowed.
 	Overflow,
@@ -213,41 +523,96 @@ pub enum BidError {
 	Underflow,
 }
 
-impl<AccountId> Bid<AccountId> {
+impl<AccountId, BlockNumber: Default> Bid<AccountId, BlockNumber> {
 	/// Create a new bid.
-	fn new(account: AccountId, price: Perbill, quantity: Coins) -> Bid<AccountId> {
+	///
+	/// The `sequence` and the `expiration` are assigned when adding the bid to the queue.
+	///
+	/// ```
+	/// use pallet_stablecoin::Bid;
+	/// use sp_runtime::Perbill;
+	///
+	/// let bid: Bid<u64, u32> = Bid::new(1, Perbill::from_percent(80), 100);
+	/// assert_eq!(*bid.account(), 1);
+	/// assert_eq!(bid.price(), Perbill::from_percent(80));
+	/// assert_eq!(bid.quantity(), 100);
+	/// ```
+	pub fn new(account: AccountId, price: Perbill, quantity: Coins) -> Bid<AccountId, BlockNumber> {
 		Bid {
 			account,
 			price,
 			quantity,
+			sequence: 0,
+			expiration: Default::default(),
+			// fine because Perbill has a multiplication tuned for balance types
+			payment: price * quantity,
 		}
 	}
 
+	/// Return the bidder.
+	pub fn account(&self) -> &AccountId {
+		&self.account
+	}
+
+	/// Return the price as a percentage of 1 coin.
+	pub fn price(&self) -> Perbill {
+		self.price
+	}
+
+	/// Return the amount of Coins gained on payout of the corresponding bond.
+	pub fn quantity(&self) -> Coins {
+		self.quantity
+	}
+
+	/// Return the block at which the bid expires.
+	pub fn expiration(&self) -> &BlockNumber {
+		&self.expiration
+	}
+
 	/// Return the amount of Coins to be payed for this bid.
+	///
+	/// Starts as `price * quantity` rounded to the nearest coin and is reduced by exactly the Coins
+	/// removed with `remove_coins` to match the reserve of the bidder.
+	///
+	/// Never exceeds the quantity because the price is at most 100%, so it cannot overflow
+	/// even for a quantity of `u64::MAX`.
 	fn payment(&self) -> Coins {
-		// This naive multiplication is fine because Perbill has an implementation tuned for balance types.
-		self.price * self.quantity
+		self.payment
 	}
 
 	/// Remove `coins` amount of Coins from the bid, mirroring the changes in quantity
 	/// according to the price attached to the bid.
+	///
+	/// The payment is reduced by exactly `coins`.
+	///
+	/// Returns the removed quantity, i.e. the bond payout credited for `coins`.
+	///
+	/// The removed quantity is `coins / price` **rounded down**, so rounding favors the system:
+	/// the bidder is never credited more bond payout than the paid `coins` are worth at the bid price.
+	///
+	/// Never fails for `coins < self.payment()`, but note that `payment` is rounded to the nearest coin:
+	/// If it was rounded up, removing the whole `payment` underflows the quantity.
 	fn remove_coins(&mut self, coins: Coins) -> Result<Coins, BidError> {
 		// Inverse price is needed because `self.price` converts from amount of bond payout coins to payment coins,
 		// but we need to convert the other way from payment coins to bond payout coins.
 		// `self.price` equals the fraction of coins I'm willing to pay now in exchange for a bond.
 		// But we need to calculate the amount of bond payouts corresponding to the coins I'm willing to pay now
 		// which means we need to use the inverse of self.price!
-		let inverse_price: Ratio<u64> = Ratio::new(Perbill::ACCURACY.into(), self.price.deconstruct().into());
-
-		// Should never overflow, but better safe than sorry.
-		let removed_quantity = inverse_price
-			.checked_mul(&coins.into())
-			.map(|r| r.to_integer())
+		// Calculating in `u128` cannot overflow because `coins * ACCURACY < u64::MAX * u32::MAX`,
+		// so this only fails for a price of zero.
+		let removed_quantity = u128::from(coins)
+			.checked_mul(Perbill::ACCURACY.into())
+			.and_then(|c| c.checked_div(self.price.deconstruct().into()))
 			.ok_or(BidError::Overflow)?;
-		self.quantity = self
+		// The result only exceeds `u64` if it exceeds the quantity as well.
+		let removed_quantity = u64::try_from(removed_quantity).map_err(|_| BidError::Underflow)?;
+		let quantity = self
 			.quantity
 			.checked_sub(removed_quantity)
 			.ok_or(BidError::Underflow)?;
+		let payment = self.payment.checked_sub(coins).ok_or(BidError::Underflow)?;
+		self.quantity = quantity;
+		self.payment = payment;
 		Ok(removed_quantity)
 	}
 }
@@ -259,17 +624,28 @@ decl_event!(
 		BlockNumber = <T as system::Trait>::BlockNumber,
 	{
 		/// Successful transfer from the first to the second account.
-		Transfer(AccountId, AccountId, u64),
+		///
+		/// Also contains the updated balances of the first and second account.
+		Transfer(AccountId, AccountId, u64, u64, u64),
+		/// Shares were transferred from the first to the second account.
+		SharesTransferred(AccountId, AccountId, u64),
 		/// New bid was registered for the account at given price and amount.
 		NewBid(AccountId, Perbill, u64),
 		/// A bid was refunded (repayed and removed from the queue).
 		RefundedBid(AccountId, u64),
+		/// A bid of the account at the price and quantity expired and was removed from the bidding queue.
+		BidExpired(AccountId, Perbill, u64),
+		/// A bid of the account was partially converted to a bond: converted quantity and remaining quantity.
+		BidPartiallyConverted(AccountId, u64, u64),
 		/// A new bond was created for the account with payout and expiration.
 		NewBond(AccountId, u64, BlockNumber),
-		/// A bond was payed out to the account.
-		BondFulfilled(AccountId, u64),
-		/// A bond was partially payed out to the account.
-		BondPartiallyFulfilled(AccountId, u64),
+		/// Summary of the bonds created by a contraction: number of bonds, total payout and
+		/// the expiration of the last bond.
+		BondsCreated(u32, u64, BlockNumber),
+		/// A bond was payed out to the account: payout and the amount of the expansion left afterwards.
+		BondFulfilled(AccountId, u64, u64),
+		/// A bond was partially payed out to the account: the amount paid and the payout still outstanding.
+		BondPartiallyFulfilled(AccountId, u64, u64),
 		/// A bond expired and was removed from the bond queue.
 		BondExpired(AccountId, u64),
 		/// All bids at and above the given price were cancelled for the account.
@@ -278,10 +654,50 @@ decl_event!(
 		CancelledBidsBelow(AccountId, Perbill),
 		/// All bids were cancelled for the account.
 		CancelledBids(AccountId),
+		/// All bids with a price between the given prices (inclusive) were cancelled for the account.
+		CancelledBidsInRange(AccountId, Perbill, Perbill),
+		/// A single bid at the given price and quantity was cancelled for the account.
+		CancelledBid(AccountId, Perbill, u64),
 		/// The supply was expanded by the amount.
 		ExpandedSupply(u64),
 		/// The supply was contracted by the amount.
 		ContractedSupply(u64),
+		/// The bids could not cover the contraction: the intended amount and the amount actually burned.
+		ContractionShortfall(u64, u64),
+		/// The supply could not be contracted by the amount because there are no bids.
+		ContractionFailedNoBids(u64),
+		/// The account burned the amount of its Coins.
+		Burned(AccountId, u64),
+		/// The supply changed from the first to the second amount because of an expansion or contraction.
+		SupplyChanged(u64, u64),
+		/// The minimum bond price was changed by governance.
+		MinimumBondPriceUpdated(Perbill),
+		/// The adjustment frequency was changed by governance.
+		AdjustmentFrequencyUpdated(BlockNumber),
+		/// The target unit the price is pegged to was changed by governance.
+		TargetUnitUpdated(u64),
+		/// The account was frozen by governance.
+		Frozen(AccountId),
+		/// The account was thawed by governance.
+		Thawed(AccountId),
+		/// The amount of new shares was issued to the account by governance.
+		SharesMinted(AccountId, u64),
+		/// The pallet was paused by governance.
+		Paused,
+		/// The pallet was unpaused by governance.
+		Unpaused,
+		/// The price was observed at the block and used to adjust the supply.
+		PriceObserved(BlockNumber, u64),
+		/// The supply adjustment at the block was skipped because the price feed is stale.
+		AdjustmentSkippedStalePrice(BlockNumber),
+		/// The supply adjustment at the block was skipped because the price is outside of
+		/// `MinPriceBound` and `MaxPriceBound`.
+		PriceOutOfBounds(BlockNumber, u64),
+		/// The supply adjustment at the block failed with the error.
+		AdjustmentFailed(BlockNumber, DispatchError),
+		/// The price is back within the tolerance around the peg at the block after the previous
+		/// adjustment expanded or contracted the supply.
+		ReturnedToPeg(BlockNumber),
 	}
 );
 
@@ -302,12 +718,52 @@ decl_error! {
 		GenericOverflow,
 		/// An arithmetic operation caused an underflow.
 		GenericUnderflow,
-		/// The bidder tried to pay more than 100% for a bond.
+		/// The bidder tried to pay more than 100% for a bond or the minimum bond price was set to 100%.
 		BondPriceOver100Percent,
 		/// The bidding price is below `MinimumBondPrice`.
 		BondPriceTooLow,
-		/// The bond being bid for is not big enough (in amount of Coins).
+		/// The bond being bid for is smaller than `MinimumBidQuantity` (in amount of Coins).
 		BondQuantityTooLow,
+		/// There are no shares to hand out Coins to.
+		NoShares,
+		/// The account trying to transfer shares does not hold enough of them.
+		InsufficientShares,
+		/// Adding the bonds would exceed `MaximumBonds`.
+		TooManyBonds,
+		/// No price has been observed yet.
+		NoPriceObserved,
+		/// The batch contains more than `MaximumBids` bids.
+		TooManyBids,
+		/// The batch contains more than `MaxTransfersPerBatch` transfers.
+		BatchTooLarge,
+		/// The account would have more than `MaxBidsPerAccount` bids in the queue.
+		TooManyBidsForAccount,
+		/// The bidding queue is full and the bid is not higher than the lowest bid in the queue.
+		BidQueueFull,
+		/// The amount of Coins to transfer or pay is zero.
+		ZeroAmount,
+		/// There is no matching bid to cancel.
+		BidNotFound,
+		/// The lower end of the price range is greater than the upper end.
+		InvalidRange,
+		/// The payment for the bid is greater than the maximum payment given by the bidder.
+		PaymentExceedsLimit,
+		/// The account does not exist (i.e., it has no balance).
+		DeadAccount,
+		/// The transfer would leave the sender with a balance below `ExistentialDeposit`.
+		BelowExistentialDeposit,
+		/// The account is frozen.
+		AccountFrozen,
+		/// The account already made `MaxBidActionsPerBlock` bid-related calls in this block.
+		RateLimited,
+		/// The pallet is paused.
+		Paused,
+		/// The minimum bond price was set to zero.
+		ZeroMinimumBondPrice,
+		/// The adjustment frequency was set to zero.
+		ZeroAdjustmentFrequency,
+		/// The target unit was set to zero.
+		ZeroTargetUnit,
 	}
 }
 
@@ -323,46 +779,151 @@ impl<T: Trait> From<BidError> for Error<T> {
 // This pallet's storage items.
 decl_storage! {
 	trait Store for Module<T: Trait> as Stablecoin {
-		/// The allocation of shares to accounts.
+		/// The version of the storage layout used to decide which migrations to run on runtime upgrades.
+		///
+		/// 0 means the storage predates versioning.
+		StorageVersion get(fn storage_version): u16;
+
+		/// The shareholders in the order in which they receive extra Coins during a handout.
 		///
-		/// This is a `Vec` and thus should be limited to few shareholders (< 1_000).
-		/// In principle it would be possible to make shares tradeable. In that case
-		/// we would have to use a map similar to the `Balance` one.
-		Shares get(fn shares): Vec<(T::AccountId, u64)>;
+		/// Shares can be transferred with `transfer_shares` which might add or remove shareholders.
+		Shareholders get(fn shareholders): Vec<T::AccountId>;
+		/// The amount of shares held by each shareholder.
+		Shares get(fn shares_of): map hasher(blake2_128_concat) T::AccountId => u64;
+		/// The total amount of shares.
+		ShareSupply get(fn share_supply): u64;
 
 		/// The balance of stablecoin associated with each account.
 		Balance get(fn get_balance): map hasher(blake2_128_concat) T::AccountId => Coins;
+		/// The Coins of each account reserved for its bids in the bidding queue.
+		///
+		/// Reserved Coins are not part of the (free) `Balance` and cannot be transferred.
+		Reserved get(fn reserved_balance): map hasher(blake2_128_concat) T::AccountId => Coins;
 
 		/// The total amount of Coins in circulation.
 		CoinSupply get(fn coin_supply): Coins = 0;
 
 		/// The available bonds for contracting supply.
 		Bonds get(fn get_bond): map hasher(twox_64_concat) BondIndex => Bond<T::AccountId, T::BlockNumber>;
-		/// Start and end index pair used to implement a ringbuffer on top of the `Bonds` map.
+		/// Start index and length used to implement a ringbuffer on top of the `Bonds` map.
+		///
+		/// Indices wrap around at `BondIndex::max_value()`, so the start can be bigger than the
+		/// index of the last bond.
 		BondsRange get(fn bonds_range): (BondIndex, BondIndex) = (0, 0);
+		/// The indices of the bonds in the `Bonds` map for each account, in queue order.
+		BondsByAccount get(fn bond_indices_of): map hasher(blake2_128_concat) T::AccountId => Vec<BondIndex>;
+
+		/// The number of bid-related calls of each account in a block.
+		///
+		/// Used to enforce `MaxBidActionsPerBlock`. The counts of a block are removed in its `on_finalize`.
+		BidActions get(fn bid_actions): double_map hasher(twox_64_concat) T::BlockNumber, hasher(blake2_128_concat) T::AccountId => u32;
+
+		/// Accounts frozen by governance, see `freeze_account`.
+		Frozen get(fn is_frozen): map hasher(blake2_128_concat) T::AccountId => bool;
+		/// Whether the pallet is paused by governance, see `set_paused`.
+		Paused get(fn paused): bool;
+
+		/// The minimum percentage to pay for a bond if it was changed from `MinimumBondPrice` by governance.
+		MinimumBondPriceOverride get(fn minimum_bond_price_override): Option<Perbill>;
+		/// The number of blocks between supply adjustments if it was changed from `AdjustmentFrequency` by governance.
+		AdjustmentFrequencyOverride get(fn adjustment_frequency_override): Option<T::BlockNumber>;
+		/// The price the Coins are pegged to if it was changed from `BaseUnit` by governance.
+		TargetUnitOverride get(fn target_unit_override): Option<Coins>;
+
+		/// The position in the shareholders of the shareholder receiving the first extra coin in the next handout.
+		HandoutCursor get(fn handout_cursor): u64;
+
+		/// The total amount of Coins locked in the bidding queue (payed for bids but not converted yet).
+		LockedInBids get(fn total_locked_in_bids): Coins;
+
+		/// The total amount of Coins ever handed out to shareholders (including the initial supply).
+		TotalMintedToShares get(fn total_minted_to_shares): Coins;
+		/// The total amount of Coins ever burned by contracting the supply.
+		TotalBurned get(fn total_burned): Coins;
+		/// The total amount of Coins ever paid out to bonds.
+		TotalBondPayouts get(fn total_bond_payouts): Coins;
+		/// The action of the last successful price-based supply adjustment.
+		LastAdjustmentAction get(fn last_adjustment_action): Option<SupplyAction>;
+		/// The last price fetched from `CoinPrice` that was not stale.
+		LastObservedPrice get(fn last_observed_price): Option<Coins>;
+
+		/// The last price returned by the `ClampedPrice` oracle adapter.
+		LastClampedPrice: Option<Coins>;
+		/// The last prices recorded by the `MovingAveragePrice` oracle adapter.
+		PriceHistory: map hasher(twox_64_concat) PriceIndex => Coins;
+		/// Start and length pair used to implement a ringbuffer on top of the `PriceHistory` map.
+		PriceHistoryRange: (PriceIndex, PriceIndex) = (0, 0);
 
 		/// The current bidding queue for bonds.
-		BondBids get(fn bond_bids): Vec<Bid<T::AccountId>>;
+		///
+		/// Stored as a binary max-heap (see `BoundedPriorityQueue`), use `bond_bids` for a sorted view.
+		BondBids: Vec<Bid<T::AccountId, T::BlockNumber>>;
+		/// The sequence number assigned to the next bid added to the queue.
+		NextBidSequence get(fn next_bid_sequence): u64;
 	}
 	add_extra_genesis {
 		/// The shareholders to initialize the stablecoin with.
 		config(shareholders):
 			Vec<(T::AccountId, u64)>;
+		/// Initial balances of Coins. If empty, `InitialSupply` is handed out to the shareholders instead.
+		///
+		/// Must sum up to `InitialSupply` otherwise.
+		config(balances):
+			Vec<(T::AccountId, Coins)>;
 		build(|config: &GenesisConfig<T>| {
+			assert!(T::BaseUnit::get() > 0, "`BaseUnit` needs to be greater than zero");
+			assert!(
+				T::MinimumSupply::get() >= T::BaseUnit::get(),
+				"`MinimumSupply` needs to be at least `BaseUnit`"
+			);
 			assert!(
 				T::MinimumSupply::get() < T::InitialSupply::get(),
-				"initial coin supply needs to be greater than the minimum"
+				"`InitialSupply` needs to be greater than `MinimumSupply`"
 			);
 
 			assert!(!config.shareholders.is_empty(), "need at least one shareholder");
-			// TODO: make sure shareholders are unique?
+			assert!(
+				config.shareholders.iter().all(|(_acc, num_shares)| *num_shares > 0),
+				"every shareholder needs at least one share"
+			);
+			let mut accounts: Vec<&T::AccountId> =
+				config.shareholders.iter().map(|(acc, _num_shares)| acc).collect();
+			accounts.sort();
+			assert!(
+				accounts.windows(2).all(|pair| pair[0] != pair[1]),
+				"shareholders need to be unique"
+			);
 
-			// Hand out the initial coin supply to the shareholders.
-			<Module<T>>::hand_out_coins(&config.shareholders, T::InitialSupply::get(), <Module<T>>::coin_supply())
-				.expect("initialization handout should not fail");
+			if config.balances.is_empty() {
+				// Hand out the initial coin supply to the shareholders.
+				<Module<T>>::hand_out_coins(&config.shareholders, T::InitialSupply::get(), <Module<T>>::coin_supply())
+					.expect("initialization handout should not fail");
+			} else {
+				let total = config
+					.balances
+					.iter()
+					.try_fold(0 as Coins, |total, (_acc, balance)| total.checked_add(*balance));
+				assert!(
+					total == Some(T::InitialSupply::get()),
+					"initial `balances` need to sum up to `InitialSupply`"
+				);
+				for (acc, balance) in config.balances.iter() {
+					<Module<T>>::add_balance(acc, *balance);
+				}
+				<CoinSupply>::put(T::InitialSupply::get());
+				<TotalMintedToShares>::put(T::InitialSupply::get());
+			}
 
 			// Store the shareholders with their shares.
-			<Shares<T>>::put(&config.shareholders);
+			for (acc, num_shares) in config.shareholders.iter() {
+				if !<Shares<T>>::contains_key(acc) {
+					<Shareholders<T>>::mutate(|holders| holders.push(acc.clone()));
+				}
+				<Shares<T>>::mutate(acc, |shares| *shares += num_shares);
+				<ShareSupply>::mutate(|supply| *supply += num_shares);
+			}
+
+			<StorageVersion>::put(CURRENT_STORAGE_VERSION);
 		});
 	}
 }
@@ -372,40 +933,228 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		/// The minimum percentage to pay for a bond.
 		const MinimumBondPrice: Perbill = T::MinimumBondPrice::get();
+		/// The bonus added to the payout of new bonds.
+		const BondBonus: Perbill = T::BondBonus::get();
 		/// The expiration period for a bond.
 		const ExpirationPeriod: T::BlockNumber = T::ExpirationPeriod::get();
+		/// The expiration period for a bid.
+		const BidExpirationPeriod: T::BlockNumber = T::BidExpirationPeriod::get();
 		/// The amount of stablecoins that represent 1 external value (e.g., 1$).
 		const BaseUnit: Coins = T::BaseUnit::get();
+		/// The minimum quantity of a bid for a bond.
+		const MinimumBidQuantity: Coins = T::MinimumBidQuantity::get();
+		/// The lowest price used to adjust the supply.
+		const MinPriceBound: Coins = T::MinPriceBound::get();
+		/// The highest price used to adjust the supply.
+		const MaxPriceBound: Coins = T::MaxPriceBound::get();
+		/// The ticker symbol of the Coins.
+		const Symbol: &'static [u8] = T::Symbol::get();
 		/// The maximum amount of bids in the bidding queue.
 		const MaximumBids: u64 = T::MaximumBids::get();
+		/// The maximum amount of transfers in a batch.
+		const MaxTransfersPerBatch: u32 = T::MaxTransfersPerBatch::get();
+		/// The maximum amount of bids per account in the bidding queue.
+		const MaxBidsPerAccount: u64 = T::MaxBidsPerAccount::get();
+		/// The maximum amount of bid-related calls per account and block.
+		const MaxBidActionsPerBlock: u32 = T::MaxBidActionsPerBlock::get();
+		/// What happens to new bids if the bidding queue is full.
+		const BidEvictionPolicy: BidEvictionPolicy = T::BidEvictionPolicy::get();
+		/// The maximum amount of bonds in the bonds queue.
+		const MaximumBonds: BondIndex = T::MaximumBonds::get();
+		/// How supply expansions are distributed among bonds.
+		const BondPayoutMode: BondPayoutMode = T::BondPayoutMode::get();
+		/// The maximum amount of Coins paid to a single bond in one supply expansion.
+		const MaxBondPayoutPerExpansion: Coins = T::MaxBondPayoutPerExpansion::get();
 		/// How often the coin supply will be adjusted based on price.
 		const AdjustmentFrequency: T::BlockNumber = T::AdjustmentFrequency::get();
+		/// The share of the price deviation corrected per adjustment.
+		const AdjustmentDamping: Perbill = T::AdjustmentDamping::get();
+		/// The relative deviation from `BaseUnit` within which no supply adjustment happens.
+		const PegTolerance: Perbill = T::PegTolerance::get();
+		/// The factor scaling supply expansions.
+		const ExpansionFactor: Perbill = T::ExpansionFactor::get();
+		/// The factor scaling supply contractions.
+		const ContractionFactor: Perbill = T::ContractionFactor::get();
 		/// The minimum amount of Coins that will be in circulation.
 		const MinimumSupply: Coins = T::MinimumSupply::get();
+		/// The maximum amount of expired bonds removed per block.
+		const MaximumBondExpirationsPerBlock: u32 = T::MaximumBondExpirationsPerBlock::get();
+		/// The minimum balance to keep after sending Coins.
+		const ExistentialDeposit: Coins = T::ExistentialDeposit::get();
 
 		fn deposit_event() = default;
 
 		/// Transfer `amount` Coins from the sender to the account `to`.
 		///
+		/// Fails with `ZeroAmount` if `amount` is zero.
+		///
 		/// **Weight:**
 		/// - complexity: `O(1)`
 		/// - DB access: 2 storage map reads + 2 storage map writes
 		pub fn send_coins(origin, to: T::AccountId, amount: u64) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::transfer_from_to(&sender, &to, amount)?;
-			Self::deposit_event(RawEvent::Transfer(sender, to, amount));
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			ensure!(amount > 0, Error::<T>::ZeroAmount);
+			let (sender_balance, receiver_balance) = Self::do_transfer(&sender, &to, amount)?;
+			Self::deposit_event(RawEvent::Transfer(sender, to, amount, sender_balance, receiver_balance));
+			Ok(())
+		}
+
+		/// Transfer Coins from the sender to several accounts, given as `(to, amount)` pairs.
+		///
+		/// Either all transfers succeed or none is made: Fails with `InsufficientBalance` if the
+		/// sender cannot pay for all of them, with `BalanceOverflow` if their total overflows and
+		/// with `BatchTooLarge` if there are more than `MaxTransfersPerBatch` transfers.
+		/// Otherwise every transfer is checked like in `send_coins`.
+		///
+		/// Transfers to the sender do not move any Coins, so they do not count toward the total
+		/// and the `ExistentialDeposit` is checked against the balance left after the other transfers.
+		/// Like in `send_coins`, the sender must still be able to pay for each of them on its own.
+		///
+		/// **Weight:**
+		/// - complexity: `O(N)`
+		///   - `N` being the number of transfers, limited to `MaxTransfersPerBatch`
+		/// - DB access:
+		///   - 1 storage map read and write for the sender's balance
+		///   - `N` storage map reads and writes for the receivers' balances
+		///   - `N + 1` storage map reads for frozen accounts
+		pub fn transfer_batch(origin, transfers: Vec<(T::AccountId, Coins)>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			ensure!(
+				transfers.len() as u64 <= u64::from(T::MaxTransfersPerBatch::get()),
+				Error::<T>::BatchTooLarge
+			);
+			Self::ensure_not_frozen(&sender)?;
+			let mut total: Coins = 0;
+			let mut largest_to_self: Coins = 0;
+			for (to, amount) in transfers.iter() {
+				ensure!(*amount > 0, Error::<T>::ZeroAmount);
+				Self::ensure_not_frozen(to)?;
+				if *to == sender {
+					largest_to_self = largest_to_self.max(*amount);
+				} else {
+					total = total.checked_add(*amount).ok_or(Error::<T>::BalanceOverflow)?;
+				}
+			}
+			let mut sender_balance = Self::get_balance(&sender);
+			ensure!(largest_to_self <= sender_balance, Error::<T>::InsufficientBalance);
+			let updated_sender_balance = sender_balance
+				.checked_sub(total)
+				.ok_or(Error::<T>::InsufficientBalance)?;
+			ensure!(
+				updated_sender_balance == 0 || updated_sender_balance >= T::ExistentialDeposit::get(),
+				Error::<T>::BelowExistentialDeposit
+			);
+			// ↑ verify ↑
+			// ↓ update ↓
+			// Receiving cannot overflow because all balances together are bounded by the coin supply.
+			for (to, amount) in transfers {
+				let receiver_balance = if to == sender {
+					// sender and receiver share the same balance --> nothing to move
+					sender_balance
+				} else {
+					// cannot underflow because the sender can pay for all transfers
+					sender_balance -= amount;
+					Self::add_balance(&to, amount);
+					Self::get_balance(&to)
+				};
+				Self::deposit_event(RawEvent::Transfer(
+					sender.clone(),
+					to,
+					amount,
+					sender_balance,
+					receiver_balance,
+				));
+			}
+			Self::set_balance(&sender, sender_balance);
+
+			Ok(())
+		}
+
+		/// Transfer `amount` shares from the sender to the account `to`.
+		///
+		/// Adds `to` to the shareholders if necessary and removes the sender from the shareholders
+		/// if it has no shares left. The total amount of shares is not changed.
+		/// Transferring to oneself or transferring zero shares does nothing.
+		///
+		/// **Weight:**
+		/// - complexity: `O(S)`
+		///   - `S` being the number of shareholders
+		/// - DB access:
+		///   - 2 reads and writes for the shares of sender and receiver
+		///   - 1 read and write of the shareholders if a shareholder is added or removed
+		pub fn transfer_shares(origin, to: T::AccountId, amount: u64) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			if sender == to || amount == 0 {
+				return Ok(());
+			}
+			Self::ensure_not_frozen(&sender)?;
+			Self::ensure_not_frozen(&to)?;
+			ensure!(<Shares<T>>::contains_key(&sender), Error::<T>::InsufficientShares);
+			let remaining = Self::shares_of(&sender)
+				.checked_sub(amount)
+				.ok_or(Error::<T>::InsufficientShares)?;
+			let is_new_shareholder = !<Shares<T>>::contains_key(&to);
+			let updated_to_shares = Self::shares_of(&to)
+				.checked_add(amount)
+				.ok_or(Error::<T>::GenericOverflow)?;
+			// ↑ verify ↑
+			// ↓ update ↓
+			<Shares<T>>::insert(&to, updated_to_shares);
+			if is_new_shareholder {
+				<Shareholders<T>>::mutate(|holders| holders.push(to.clone()));
+			}
+			if remaining == 0 {
+				<Shares<T>>::remove(&sender);
+				<Shareholders<T>>::mutate(|holders| holders.retain(|acc| *acc != sender));
+			} else {
+				<Shares<T>>::insert(&sender, remaining);
+			}
+			Self::deposit_event(RawEvent::SharesTransferred(sender, to, amount));
+
+			Ok(())
+		}
+
+		/// Burn `amount` Coins of the sender, removing them from the coin supply.
+		///
+		/// Fails with `CoinSupplyUnderflow` if the supply would drop below `MinimumSupply`
+		/// and with `AccountFrozen` if the sender is frozen.
+		///
+		/// **Weight:**
+		/// - complexity: `O(1)`
+		/// - DB access: 1 storage map write + 1 read and write of `coin_supply`
+		pub fn burn(origin, amount: Coins) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_not_frozen(&who)?;
+			let remaining_supply = Self::coin_supply()
+				.checked_sub(amount)
+				.ok_or(Error::<T>::CoinSupplyUnderflow)?;
+			ensure!(remaining_supply >= T::MinimumSupply::get(), Error::<T>::CoinSupplyUnderflow);
+			// ↑ verify ↑
+			Self::remove_balance(&who, amount)?;
+			// ↓ update ↓
+			<CoinSupply>::put(remaining_supply);
+			Self::deposit_event(RawEvent::Burned(who, amount));
+
 			Ok(())
 		}
 
 		/// Bid for `quantity` Coins at a `price`.
 		///
 		/// + `price` is a fraction of the desired payout quantity (e.g., 80%).
-		/// + Expects a `quantity` of a least `BaseUnit`.
+		/// + Expects a `quantity` of a least `MinimumBidQuantity`.
 		///
 		/// Example: `bid_for_bond(origin, Perbill::from_percent(80), 5 * BaseUnit)` will bid
 		/// for a bond with a payout of `5 * BaseUnit` Coins for a price of
 		/// `0.8 * 5 * BaseUnit = 4 * BaseUnit` Coins.
 		///
+		/// If the bidding queue is full, the bid needs to be strictly higher than the lowest bid
+		/// (which is refunded) or it fails with `BidQueueFull`. With `BidEvictionPolicy::RejectNew`
+		/// it always fails with `BidQueueFull` if the queue is full.
+		///
 		/// **Weight:**
 		/// - complexity: `O(B)`
 		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
@@ -415,116 +1164,518 @@ decl_module! {
 		///   - 1 potential DB storage map write to refund evicted bid
 		pub fn bid_for_bond(origin, price: Perbill, quantity: Coins) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+
+			Self::place_bid(Bid::new(who, price, quantity), None)
+		}
+
+		/// Bid for `quantity` Coins at a `price` like `bid_for_bond`, but pay at most `max_payment`.
+		///
+		/// Fails with `PaymentExceedsLimit` if the payment for the bid (`price * quantity`, which
+		/// is subject to `Perbill` rounding) is greater than `max_payment`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(B)`
+		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+		/// - DB access: same as `bid_for_bond`
+		pub fn bid_for_bond_limited(origin, price: Perbill, quantity: Coins, max_payment: Coins) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
 
-			ensure!(price <= Perbill::from_percent(100), Error::<T>::BondPriceOver100Percent);
-			ensure!(price > T::MinimumBondPrice::get(), Error::<T>::BondPriceTooLow);
-			ensure!(quantity >= T::BaseUnit::get(), Error::<T>::BondQuantityTooLow);
+			Self::place_bid(Bid::new(who, price, quantity), Some(max_payment))
+		}
 
-			let bid = Bid::new(who.clone(), price, quantity);
+		/// Bid for several bonds at once, given as `(price, quantity)` pairs.
+		///
+		/// Every bid is validated like in `bid_for_bond` and the batch is limited to `MaximumBids`.
+		/// The sender cannot end up with more than `MaxBidsPerAccount` bids in the queue.
+		/// If any bid is invalid or the sender cannot pay for all of them, no bid is placed.
+		///
+		/// **Weight:**
+		/// - complexity: `O(N + B)`
+		///   - `N` being the number of bids in the batch, limited to `MaximumBids`
+		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+		/// - DB access:
+		///   - read and write bids from and to DB
+		///   - 1 DB storage map write to pay the bids
+		///   - up to `N` potential DB storage map writes to refund evicted bids
+		pub fn bid_for_bonds(origin, bids: Vec<(Perbill, Coins)>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+
+			Self::ensure_not_frozen(&who)?;
+			Self::ensure_not_rate_limited(&who)?;
+			ensure!(bids.len() as u64 <= T::MaximumBids::get(), Error::<T>::TooManyBids);
+			ensure!(
+				Self::bid_count_of(&who).saturating_add(bids.len() as u64) <= T::MaxBidsPerAccount::get(),
+				Error::<T>::TooManyBidsForAccount
+			);
+			let total_payment = Self::total_payment_for(&who, &bids)?;
+			Self::ensure_bids_fit(bids.iter().map(|(price, _quantity)| *price))?;
 
 			// ↑ verify ↑
-			Self::remove_balance(&who, bid.payment())?;
+			Self::reserve(&who, total_payment)?;
 			// ↓ update ↓
-			Self::add_bid(bid);
-			Self::deposit_event(RawEvent::NewBid(who, price, quantity));
+			Self::push_bids(&who, bids, total_payment);
+			Self::note_bid_action(&who);
 
 			Ok(())
 		}
 
-		/// Cancel all bids at or below `price` of the sender and refund the Coins.
+		/// Cancel all bids at or below `cancel_below` of the sender and place `new_bids` instead.
+		///
+		/// The new bids are validated like in `bid_for_bonds`, taking into account that the
+		/// cancelled bids are removed and refunded first.
+		/// If any new bid is invalid, no bid is cancelled and no bid is placed.
 		///
 		/// **Weight:**
-		/// - complexity: `O(B)`
+		/// - complexity: `O((B + N) log B)`
+		///   - `N` being the number of new bids, limited to `MaximumBids`
 		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
-		/// - DB access: read and write bids from and to DB
-		pub fn cancel_bids_at_or_below(origin, price: Perbill) -> DispatchResult {
+		/// - DB access:
+		///   - read and write bids from and to DB
+		///   - up to `B` refunds of cancelled bids
+		///   - 1 DB storage map write to pay the new bids
+		///   - up to `N` potential DB storage map writes to refund evicted bids
+		pub fn replace_bids(origin, cancel_below: Perbill, new_bids: Vec<(Perbill, Coins)>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+
+			Self::ensure_not_frozen(&who)?;
+			Self::ensure_not_rate_limited(&who)?;
+			ensure!(new_bids.len() as u64 <= T::MaximumBids::get(), Error::<T>::TooManyBids);
+			let is_cancelled = |bid: &Bid<T::AccountId, T::BlockNumber>| bid.account == who && bid.price <= cancel_below;
+			let (cancelled_count, cancelled_payment) = <BondBids<T>>::get()
+				.iter()
+				.filter(|bid| is_cancelled(bid))
+				.fold((0u64, 0 as Coins), |(count, payment), bid| {
+					(count + 1, payment.saturating_add(bid.payment()))
+				});
+			ensure!(
+				Self::bid_count_of(&who)
+					.saturating_sub(cancelled_count)
+					.saturating_add(new_bids.len() as u64)
+					<= T::MaxBidsPerAccount::get(),
+				Error::<T>::TooManyBidsForAccount
+			);
+			let total_payment = Self::total_payment_for(&who, &new_bids)?;
+			Self::ensure_bids_fit_after_cancelling(new_bids.iter().map(|(price, _quantity)| *price), is_cancelled)?;
+			// refunds are limited by the reserved balance
+			let reserved = Self::reserved_balance(&who);
+			let refund = min(cancelled_payment, reserved);
+			// Calculate the balances after refunding and paying up front, so nothing can fail
+			// once the bids are cancelled.
+			let updated_free = Self::get_balance(&who)
+				.checked_add(refund)
+				.ok_or(Error::<T>::BalanceOverflow)?
+				.checked_sub(total_payment)
+				.ok_or(Error::<T>::InsufficientBalance)?;
+			// safe because `refund <= reserved`
+			let updated_reserved = (reserved - refund)
+				.checked_add(total_payment)
+				.ok_or(Error::<T>::BalanceOverflow)?;
+
 			// ↑ verify ↑
 			// ↓ update ↓
-			Self::cancel_bids(|bid| bid.account == who && bid.price <= price);
-			Self::deposit_event(RawEvent::CancelledBidsBelow(who, price));
+			// refunds exactly `refund` because only bids of `who` are cancelled
+			Self::cancel_bids(is_cancelled);
+			Self::deposit_event(RawEvent::CancelledBidsBelow(who.clone(), cancel_below));
+			Self::set_balance(&who, updated_free);
+			Self::set_reserved(&who, updated_reserved);
+			Self::push_bids(&who, new_bids, total_payment);
+			Self::note_bid_action(&who);
 
 			Ok(())
 		}
 
-		pub fn cancel_bids_at_or_above(origin, price: Perbill) -> DispatchResult {
-			let who = ensure_signed(origin)?;
+		/// Set the minimum percentage to pay for a bond, overriding `MinimumBondPrice`.
+		///
+		/// Must be below 100% because bids have to pay more than the minimum bond price.
+		/// Can only be called by `AdminOrigin`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(1)`
+		/// - DB access: 1 write
+		pub fn set_minimum_bond_price(origin, price: Perbill) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(price > Perbill::from_percent(0), Error::<T>::ZeroMinimumBondPrice);
+			ensure!(price < Perbill::from_percent(100), Error::<T>::BondPriceOver100Percent);
 			// ↑ verify ↑
 			// ↓ update ↓
-			Self::cancel_bids(|bid| bid.account == who && bid.price >= price);
-			Self::deposit_event(RawEvent::CancelledBidsAbove(who, price));
+			<MinimumBondPriceOverride>::put(price);
+			Self::deposit_event(RawEvent::MinimumBondPriceUpdated(price));
 
 			Ok(())
 		}
 
-		/// Cancel all bids belonging to the sender and refund the Coins.
+		/// Set the number of blocks between supply adjustments, overriding `AdjustmentFrequency`.
+		///
+		/// Can only be called by `AdminOrigin`.
 		///
 		/// **Weight:**
-		/// - complexity: `O(B)`
-		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
-		/// - DB access: read and write bids from and to DB
-		pub fn cancel_all_bids(origin) -> DispatchResult {
-			let who = ensure_signed(origin)?;
+		/// - complexity: `O(1)`
+		/// - DB access: 1 write
+		pub fn set_adjustment_frequency(origin, frequency: T::BlockNumber) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!frequency.is_zero(), Error::<T>::ZeroAdjustmentFrequency);
 			// ↑ verify ↑
 			// ↓ update ↓
-			Self::cancel_bids(|bid| bid.account == who);
-			Self::deposit_event(RawEvent::CancelledBids(who));
+			<AdjustmentFrequencyOverride<T>>::put(frequency);
+			Self::deposit_event(RawEvent::AdjustmentFrequencyUpdated(frequency));
 
 			Ok(())
 		}
 
-		/// Adjust the amount of Coins according to the price.
+		/// Set the price the Coins are pegged to, overriding `BaseUnit` for the supply adjustment.
+		///
+		/// This allows tracking a different reference value without changing the denomination
+		/// of the Coins (which stays `BaseUnit`).
+		/// Can only be called by `AdminOrigin`.
 		///
 		/// **Weight:**
-		/// - complexity: `O(F + P)`
-		///   - `F` being the complexity of `CoinPrice::fetch_price()`
-		///   - `P` being the complexity of `on_block_with_price`
-		fn on_initialize(n: T::BlockNumber) {
-			let price = T::CoinPrice::fetch_price();
-			Self::on_block_with_price(n, price).unwrap_or_else(|e| {
-				native::error!("could not adjust supply: {:?}", e);
-			});
+		/// - complexity: `O(1)`
+		/// - DB access: 1 write
+		pub fn set_target_unit(origin, target: Coins) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(target > 0, Error::<T>::ZeroTargetUnit);
+			// ↑ verify ↑
+			// ↓ update ↓
+			<TargetUnitOverride>::put(target);
+			Self::deposit_event(RawEvent::TargetUnitUpdated(target));
+
+			Ok(())
 		}
-	}
-}
 
-// Implement the BasicCurrency to allow other pallets to interact programmatically
-// with the Stablecoin.
-impl<T: Trait> BasicCurrency<T::AccountId> for Module<T> {
-	type Balance = Coins;
+		/// Freeze the account `who`.
+		///
+		/// A frozen account cannot send or receive Coins via transfers, burn Coins, bid for bonds or
+		/// send or receive shares. The `Currency` implementation refuses withdrawals from and deposits
+		/// to it as well. It still receives bond payouts and its share of supply expansions, can be
+		/// slashed and can cancel its bids.
+		/// Can only be called by `AdminOrigin`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(1)`
+		/// - DB access: 1 write
+		pub fn freeze_account(origin, who: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			// ↑ verify ↑
+			// ↓ update ↓
+			<Frozen<T>>::insert(&who, true);
+			Self::deposit_event(RawEvent::Frozen(who));
 
-	/// Return the amount of Coins in circulation.
-	///
-	/// **Weight:**
-	/// - complexity: `O(1)`
-	/// - DB access: 1 read
-	fn total_issuance() -> Self::Balance {
-		Self::coin_supply()
-	}
+			Ok(())
+		}
 
-	/// Return the balance of the given account.
-	///
-	/// **Weight:**
-	/// - complexity: `O(1)`
-	/// - DB access: 1 read from balance storage map
-	fn total_balance(who: &T::AccountId) -> Self::Balance {
-		Self::get_balance(who)
-	}
+		/// Thaw the frozen account `who`, see `freeze_account`.
+		///
+		/// Can only be called by `AdminOrigin`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(1)`
+		/// - DB access: 1 write
+		pub fn thaw_account(origin, who: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			// ↑ verify ↑
+			// ↓ update ↓
+			<Frozen<T>>::remove(&who);
+			Self::deposit_event(RawEvent::Thawed(who));
 
-	/// Return the free balance of the given account.
-	///
-	/// Equal to `total_balance` for this stablecoin.
-	///
-	/// **Weight:**
-	/// - complexity: `O(1)`
-	/// - DB access: 1 read from balance storage map
-	fn free_balance(who: &T::AccountId) -> Self::Balance {
-		Self::get_balance(who)
-	}
+			Ok(())
+		}
 
-	/// Cannot withdraw from stablecoin accounts. Returns `Ok(())` if `amount` is 0, otherwise returns an error.
-	fn ensure_can_withdraw(_who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
-		if amount.is_zero() {
+		/// Issue `amount` new shares to the account `to`, diluting the existing shareholders.
+		///
+		/// Adds `to` to the shareholders if necessary. Existing balances are not changed, only
+		/// future handouts are split across the larger amount of shares.
+		/// Can only be called by `AdminOrigin`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(S)`
+		///   - `S` being the number of shareholders
+		/// - DB access:
+		///   - 1 read and write each for the shares of `to` and the share supply
+		///   - 1 read and write of the shareholders if a shareholder is added
+		pub fn mint_shares(origin, to: T::AccountId, amount: u64) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			ensure!(amount > 0, Error::<T>::ZeroAmount);
+			let updated_to_shares = Self::shares_of(&to)
+				.checked_add(amount)
+				.ok_or(Error::<T>::GenericOverflow)?;
+			let updated_supply = Self::share_supply()
+				.checked_add(amount)
+				.ok_or(Error::<T>::GenericOverflow)?;
+			let is_new_shareholder = !<Shares<T>>::contains_key(&to);
+			// ↑ verify ↑
+			// ↓ update ↓
+			<Shares<T>>::insert(&to, updated_to_shares);
+			if is_new_shareholder {
+				<Shareholders<T>>::mutate(|holders| holders.push(to.clone()));
+			}
+			<ShareSupply>::put(updated_supply);
+			Self::deposit_event(RawEvent::SharesMinted(to, amount));
+
+			Ok(())
+		}
+
+		/// Pause or unpause the pallet.
+		///
+		/// While paused, all dispatchables moving Coins, shares or bids fail with `Paused` (including
+		/// `mint_shares`, `force_expand` and `force_contract`) and no supply adjustments happen.
+		/// The `Currency` implementation refuses transfers, withdrawals and deposits as well.
+		/// Only the governance dispatchables changing configuration or account flags remain
+		/// available (`set_paused`, `set_minimum_bond_price`, `set_adjustment_frequency`,
+		/// `set_target_unit`, `freeze_account` and `thaw_account`). Reading state is unaffected.
+		/// Can only be called by `AdminOrigin`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(1)`
+		/// - DB access: 1 write
+		pub fn set_paused(origin, paused: bool) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			// ↑ verify ↑
+			// ↓ update ↓
+			<Paused>::put(paused);
+			if paused {
+				Self::deposit_event(RawEvent::Paused);
+			} else {
+				Self::deposit_event(RawEvent::Unpaused);
+			}
+
+			Ok(())
+		}
+
+		/// Expand the supply by `amount` independently of the price, e.g. to bootstrap liquidity.
+		///
+		/// Pays out bonds first and hands out the rest to shareholders, like the price-driven expansion.
+		/// Can only be called by `AdminOrigin`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(S)` with `S` being the complexity of `expand_supply`
+		/// - DB access: 1 read for coin supply + the DB accesses of `expand_supply`
+		pub fn force_expand(origin, amount: Coins) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+
+			Self::expand_supply(Self::coin_supply(), amount)
+		}
+
+		/// Contract the supply by `amount` independently of the price, e.g. if the oracle is offline.
+		///
+		/// Converts bids to bonds like the price-driven contraction and respects `MinimumSupply`.
+		/// Can only be called by `AdminOrigin`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(C)` with `C` being the complexity of `contract_supply`
+		/// - DB access: 1 read for coin supply + the DB accesses of `contract_supply`
+		pub fn force_contract(origin, amount: Coins) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+
+			Self::contract_supply(Self::coin_supply(), amount).map(|_burned| ())
+		}
+
+		/// Cancel all bids at or below `price` of the sender and refund the Coins.
+		///
+		/// **Weight:**
+		/// - complexity: `O(B)`
+		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+		/// - DB access:
+		///   - read and write bids from and to DB
+		///   - 1 read and write of the bid actions of the sender
+		pub fn cancel_bids_at_or_below(origin, price: Perbill) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_not_rate_limited(&who)?;
+			// ↑ verify ↑
+			// ↓ update ↓
+			Self::cancel_bids(|bid| bid.account == who && bid.price <= price);
+			Self::note_bid_action(&who);
+			Self::deposit_event(RawEvent::CancelledBidsBelow(who, price));
+
+			Ok(())
+		}
+
+		/// Cancel all bids at or above `price` of the sender and refund the Coins.
+		///
+		/// **Weight:**
+		/// - complexity: `O(B)`
+		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+		/// - DB access:
+		///   - read and write bids from and to DB
+		///   - 1 read and write of the bid actions of the sender
+		pub fn cancel_bids_at_or_above(origin, price: Perbill) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_not_rate_limited(&who)?;
+			// ↑ verify ↑
+			// ↓ update ↓
+			Self::cancel_bids(|bid| bid.account == who && bid.price >= price);
+			Self::note_bid_action(&who);
+			Self::deposit_event(RawEvent::CancelledBidsAbove(who, price));
+
+			Ok(())
+		}
+
+		/// Cancel all bids belonging to the sender and refund the Coins.
+		///
+		/// **Weight:**
+		/// - complexity: `O(B)`
+		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+		/// - DB access:
+		///   - read and write bids from and to DB
+		///   - 1 read and write of the bid actions of the sender
+		pub fn cancel_all_bids(origin) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_not_rate_limited(&who)?;
+			// ↑ verify ↑
+			// ↓ update ↓
+			Self::cancel_bids(|bid| bid.account == who);
+			Self::note_bid_action(&who);
+			Self::deposit_event(RawEvent::CancelledBids(who));
+
+			Ok(())
+		}
+
+		/// Cancel all bids of the sender with a price between `low` and `high` (inclusive) and refund the Coins.
+		///
+		/// Fails with `InvalidRange` if `low` is greater than `high`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(B)`
+		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+		/// - DB access:
+		///   - read and write bids from and to DB
+		///   - 1 read and write of the bid actions of the sender
+		pub fn cancel_bids_in_range(origin, low: Perbill, high: Perbill) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_not_rate_limited(&who)?;
+			ensure!(low <= high, Error::<T>::InvalidRange);
+			// ↑ verify ↑
+			// ↓ update ↓
+			Self::cancel_bids(|bid| bid.account == who && low <= bid.price && bid.price <= high);
+			Self::note_bid_action(&who);
+			Self::deposit_event(RawEvent::CancelledBidsInRange(who, low, high));
+
+			Ok(())
+		}
+
+		/// Cancel a single bid of the sender at `price` for `quantity` Coins and refund it.
+		///
+		/// If the sender has several identical bids, only one of them is cancelled.
+		/// Fails with `BidNotFound` if the sender has no such bid.
+		///
+		/// **Weight:**
+		/// - complexity: `O(B)`
+		///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+		/// - DB access:
+		///   - read and write bids from and to DB
+		///   - 1 read and write of the bid actions of the sender
+		pub fn cancel_one_bid_at(origin, price: Perbill, quantity: Coins) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::paused(), Error::<T>::Paused);
+			Self::ensure_not_rate_limited(&who)?;
+			let matches =
+				|bid: &Bid<T::AccountId, T::BlockNumber>| bid.account == who && bid.price == price && bid.quantity == quantity;
+			ensure!(<BondBids<T>>::get().iter().any(matches), Error::<T>::BidNotFound);
+			// ↑ verify ↑
+			// ↓ update ↓
+			let mut cancelled = false;
+			Self::bids_transient().retain(|bid| {
+				if !cancelled && matches(bid) {
+					cancelled = true;
+					Self::refund_bid(bid);
+					return false;
+				}
+				true
+			});
+			Self::note_bid_action(&who);
+			Self::deposit_event(RawEvent::CancelledBid(who, price, quantity));
+
+			Ok(())
+		}
+
+		/// Remove expired bids and bonds and adjust the amount of Coins according to the price.
+		///
+		/// Emits `AdjustmentFailed` if the adjustment returns an error.
+		/// Does nothing while the pallet is paused.
+		///
+		/// **Weight:**
+		/// - complexity: `O(E + F + P)`
+		///   - `E` being the complexity of `purge_expired_bids` and `purge_expired_bonds`
+		///   - `F` being the complexity of `CoinPrice::fetch_price_checked()`
+		///   - `P` being the complexity of `on_block_with_price`
+		fn on_initialize(n: T::BlockNumber) {
+			if Self::paused() {
+				native::info!("pallet is paused, skipping supply adjustment");
+				return;
+			}
+			Self::purge_expired_bids();
+			Self::purge_expired_bonds(T::MaximumBondExpirationsPerBlock::get());
+			let price = T::CoinPrice::fetch_price_checked();
+			Self::on_block_with_price(n, price).unwrap_or_else(|e| {
+				native::error!("could not adjust supply: {:?}", e);
+				Self::deposit_event(RawEvent::AdjustmentFailed(n, e));
+			});
+		}
+
+		/// Remove the counts of bid-related calls made in the block (see `MaxBidActionsPerBlock`).
+		///
+		/// **Weight:**
+		/// - complexity: `O(A)` with `A` being the number of accounts that made bid-related calls in the block
+		/// - DB access: `A` removals
+		fn on_finalize(n: T::BlockNumber) {
+			<BidActions<T>>::remove_prefix(n);
+		}
+
+		/// Migrate the storage to `CURRENT_STORAGE_VERSION`.
+		///
+		/// **Weight:**
+		/// - complexity: `O(M)` with `M` being the complexity of `migrate`
+		fn on_runtime_upgrade() {
+			Self::migrate();
+		}
+	}
+}
+
+// Implement the BasicCurrency to allow other pallets to interact programmatically
+// with the Stablecoin.
+impl<T: Trait> BasicCurrency<T::AccountId> for Module<T> {
+	type Balance = Coins;
+
+	/// Return the amount of Coins in circulation.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read
+	fn total_issuance() -> Self::Balance {
+		Self::coin_supply()
+	}
+
+	/// Return the total balance of the given account, including the Coins reserved for bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 2 reads from balance storage maps
+	fn total_balance(who: &T::AccountId) -> Self::Balance {
+		Self::get_balance(who).saturating_add(Self::reserved_balance(who))
+	}
+
+	/// Return the free balance of the given account, excluding the Coins reserved for bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read from balance storage map
+	fn free_balance(who: &T::AccountId) -> Self::Balance {
+		Self::get_balance(who)
+	}
+
+	/// Cannot withdraw from stablecoin accounts. Returns `Ok(())` if `amount` is 0, otherwise returns an error.
+	fn ensure_can_withdraw(_who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() {
 			return Ok(());
 		}
 		Err(DispatchError::Other("cannot change issuance for stablecoins"))
@@ -536,6 +1687,7 @@ impl<T: Trait> BasicCurrency<T::AccountId> for Module<T> {
 	/// - complexity: `O(1)`
 	/// - DB access: 2 reads and write from and to balance storage map
 	fn transfer(from: &T::AccountId, to: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		ensure!(!Self::paused(), Error::<T>::Paused);
 		Self::transfer_from_to(from, to, amount)
 	}
 
@@ -583,20 +1735,365 @@ impl<T: Trait> BasicCurrency<T::AccountId> for Module<T> {
 	}
 }
 
+impl<T: Trait> StablecoinInterface<T::AccountId> for Module<T> {
+	/// Return the amount of Coins in circulation.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read
+	fn total_supply() -> Coins {
+		Self::coin_supply()
+	}
+
+	/// Return the balance of the given account.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read from balance storage map
+	fn balance_of(who: &T::AccountId) -> Coins {
+		Self::get_balance(who)
+	}
+
+	/// Return the configured `MinimumSupply`.
+	fn minimum_supply() -> Coins {
+		T::MinimumSupply::get()
+	}
+}
+
+/// Transient ringbuffer over the bonds in storage.
+type BondsQueue<T> = BoundedDeque<
+	Bond<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>,
+	<Module<T> as Store>::BondsRange,
+	<Module<T> as Store>::Bonds,
+	BondIndex,
+>;
+
 impl<T: Trait> Module<T> {
+	// ------------------------------------------------------------
+	// migrations
+
+	/// Migrate the storage from `StorageVersion` to `CURRENT_STORAGE_VERSION`.
+	///
+	/// Migrations are applied one version at a time, so a new layout only needs
+	/// a migration from the version before it.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)` (depends on the migrations run in the future)
+	/// - DB access: 1 read and potentially 1 write of the storage version
+	fn migrate() {
+		loop {
+			match Self::storage_version() {
+				0 => {
+					Self::migrate_index_bonds_and_lock_bids();
+					<StorageVersion>::put(1);
+				}
+				1 => {
+					Self::migrate_shares_to_map();
+					<StorageVersion>::put(2);
+				}
+				2 => {
+					Self::migrate_bonds_add_price();
+					<StorageVersion>::put(3);
+				}
+				3 => {
+					Self::migrate_reserve_bid_payments();
+					<StorageVersion>::put(4);
+				}
+				4 => {
+					Self::migrate_sequence_bids();
+					<StorageVersion>::put(5);
+				}
+				5 => {
+					Self::migrate_bids_add_expiration();
+					<StorageVersion>::put(6);
+				}
+				6 => {
+					Self::migrate_clear_bid_actions();
+					<StorageVersion>::put(7);
+				}
+				CURRENT_STORAGE_VERSION => break,
+				version => {
+					native::error!("unknown storage version {}, not migrating", version);
+					break;
+				}
+			}
+			native::info!("migrated stablecoin storage to version {}", Self::storage_version());
+		}
+	}
+
+	/// Populate `BondsByAccount` and `LockedInBids` from the bonds and bids stored in version 0.
+	///
+	/// Both were introduced without a migration, so they are missing the bonds and bids created
+	/// before them. They are rebuilt from scratch to not record anything twice.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B + BI)` with `B` being the number of bonds and `BI` the number of bids
+	/// - DB access:
+	///   - 1 read of the bonds range, `B` reads of bonds and up to `2 * B` writes of bond indices
+	///   - 1 read of the bids and 1 write of the Coins locked in bids
+	fn migrate_index_bonds_and_lock_bids() {
+		let (start, length) = Self::bonds_range();
+		let mut bond_indices = Vec::with_capacity(length as usize);
+		for offset in 0..length {
+			let index = start.wrapping_add(offset);
+			// encoded like the version 0 `Bond` struct
+			let old_bond: Option<(T::AccountId, Coins, T::BlockNumber)> =
+				unhashed::get(&<Bonds<T>>::hashed_key_for(index));
+			if let Some((account, _payout, _expiration)) = old_bond {
+				<BondsByAccount<T>>::remove(&account);
+				bond_indices.push((account, index));
+			}
+		}
+		// pushed in queue order like in `push_bonds`
+		for (account, index) in bond_indices {
+			<BondsByAccount<T>>::mutate(&account, |indices| indices.push(index));
+		}
+
+		// encoded like the version 0 `Bid` struct
+		let old_bids: Vec<(T::AccountId, Perbill, Coins)> =
+			unhashed::get(&Self::storage_key(b"BondBids")).unwrap_or_default();
+		// version 0 bids did not store their payment, so it is calculated like in `Bid::new`
+		let locked = old_bids
+			.iter()
+			.fold(0 as Coins, |locked, (_account, price, quantity)| {
+				locked.saturating_add(*price * *quantity)
+			});
+		<LockedInBids>::put(locked);
+	}
+
+	/// Move the shares from the single `Vec` stored in version 1 into the `Shares` map,
+	/// keeping the order of the shareholders in `Shareholders`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(S)` with `S` being the number of shareholders
+	/// - DB access: 1 read and kill of the old shares, `S + 2` writes
+	fn migrate_shares_to_map() {
+		// The `Vec` was stored at the raw key of the `Shares` storage item which is the prefix of the new map.
+		let old_key = Self::shares_storage_prefix();
+		let old_shares: Vec<(T::AccountId, u64)> = match unhashed::take(&old_key) {
+			Some(shares) => shares,
+			None => return,
+		};
+		let mut shareholders = Vec::with_capacity(old_shares.len());
+		let mut share_supply: u64 = 0;
+		for (acc, num_shares) in old_shares.into_iter() {
+			share_supply = share_supply.saturating_add(num_shares);
+			if !<Shares<T>>::contains_key(&acc) {
+				shareholders.push(acc.clone());
+			}
+			<Shares<T>>::mutate(&acc, |shares| *shares = shares.saturating_add(num_shares));
+		}
+		<Shareholders<T>>::put(shareholders);
+		<ShareSupply>::put(share_supply);
+	}
+
+	/// The raw storage key under which the `Shares` item is stored.
+	fn shares_storage_prefix() -> Vec<u8> {
+		Self::storage_key(b"Shares")
+	}
+
+	/// The raw storage key of the storage value `item` (`twox_128("Stablecoin") ++ twox_128(item)`).
+	fn storage_key(item: &[u8]) -> Vec<u8> {
+		let mut key = twox_128(b"Stablecoin").to_vec();
+		key.extend_from_slice(&twox_128(item));
+		key
+	}
+
+	/// Add the `price` to the bonds stored in version 2.
+	///
+	/// The originating bid price of these bonds is unknown, so it is set to zero.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the number of bonds
+	/// - DB access: 1 read of the bonds range, `B` reads and writes of bonds
+	fn migrate_bonds_add_price() {
+		let (start, length) = Self::bonds_range();
+		for offset in 0..length {
+			let index = start.wrapping_add(offset);
+			// encoded like the version 2 `Bond` struct
+			let old_bond: Option<(T::AccountId, Coins, T::BlockNumber)> =
+				unhashed::get(&<Bonds<T>>::hashed_key_for(index));
+			if let Some((account, payout, expiration)) = old_bond {
+				<Bonds<T>>::insert(
+					index,
+					Bond {
+						account,
+						payout,
+						expiration,
+						price: Perbill::zero(),
+					},
+				);
+			}
+		}
+	}
+
+	/// Record the payments of the bids in the queue as reserved balance and store them with the bids.
+	///
+	/// Before version 4 bid payments were removed from the balance without being reserved
+	/// and were calculated from price and quantity instead of being stored.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the number of bids
+	/// - DB access: 1 read and write of the bids, `B` reads and writes of reserved balances
+	fn migrate_reserve_bid_payments() {
+		let key = Self::storage_key(b"BondBids");
+		// encoded like the version 3 `Bid` struct
+		let old_bids: Vec<(T::AccountId, Perbill, Coins)> = match unhashed::get(&key) {
+			Some(bids) => bids,
+			None => return,
+		};
+		// encoded like the version 4 `Bid` struct
+		let bids: Vec<(T::AccountId, Perbill, Coins, Coins)> = old_bids
+			.into_iter()
+			.map(|(account, price, quantity)| {
+				// version 3 bids did not store their payment, so it is calculated like in `Bid::new`
+				let payment = price * quantity;
+				<Reserved<T>>::mutate(&account, |reserved| *reserved = reserved.saturating_add(payment));
+				(account, price, quantity, payment)
+			})
+			.collect();
+		unhashed::put(&key, &bids);
+	}
+
+	/// Assign sequence numbers to the bids stored in version 4.
+	///
+	/// Bids at the same price were unordered before, so they are sequenced in their storage order.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the number of bids
+	/// - DB access: 1 read and write of the bids, 1 write of the next sequence number
+	fn migrate_sequence_bids() {
+		let key = Self::storage_key(b"BondBids");
+		let old_bids: Vec<(T::AccountId, Perbill, Coins, Coins)> = match unhashed::get(&key) {
+			Some(bids) => bids,
+			None => return,
+		};
+		// encoded like the version 5 `Bid` struct
+		let bids: Vec<(T::AccountId, Perbill, Coins, u64, Coins)> = old_bids
+			.into_iter()
+			.enumerate()
+			.map(|(sequence, (account, price, quantity, payment))| {
+				(account, price, quantity, sequence as u64, payment)
+			})
+			.collect();
+		<NextBidSequence>::put(bids.len() as u64);
+		unhashed::put(&key, &bids);
+	}
+
+	/// Add the `expiration` to the bids stored in version 5.
+	///
+	/// The bids expire `BidExpirationPeriod` blocks after the migration.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the number of bids
+	/// - DB access: 1 read and write of the bids
+	fn migrate_bids_add_expiration() {
+		let key = Self::storage_key(b"BondBids");
+		let old_bids: Vec<(T::AccountId, Perbill, Coins, u64, Coins)> = match unhashed::get(&key) {
+			Some(bids) => bids,
+			None => return,
+		};
+		let expiration = Self::bid_expiration();
+		let bids: Vec<Bid<T::AccountId, T::BlockNumber>> = old_bids
+			.into_iter()
+			.map(|(account, price, quantity, sequence, payment)| Bid {
+				account,
+				price,
+				quantity,
+				sequence,
+				expiration,
+				payment,
+			})
+			.collect();
+		<BondBids<T>>::put(bids);
+	}
+
+	/// Remove the bid-related call counts stored per account in version 6.
+	///
+	/// They are stored per block and removed at the end of each block now.
+	///
+	/// **Weight:**
+	/// - complexity: `O(A)` with `A` being the number of accounts that ever made a bid-related call
+	/// - DB access: `A` removals
+	fn migrate_clear_bid_actions() {
+		unhashed::kill_prefix(&Self::storage_key(b"BidActions"));
+	}
+
+	// ------------------------------------------------------------
+	// shares
+
+	/// Return the shareholders with their shares in handout order.
+	///
+	/// **Weight:**
+	/// - complexity: `O(S)` with `S` being the number of shareholders
+	/// - DB access: 1 read of the shareholders and `S` reads of the shares
+	pub fn shares() -> Vec<(T::AccountId, u64)> {
+		Self::shareholders()
+			.into_iter()
+			.map(|acc| {
+				let num_shares = Self::shares_of(&acc);
+				(acc, num_shares)
+			})
+			.collect()
+	}
+
 	// ------------------------------------------------------------
 	// balances
 
+	/// Return the number of decimals used to display Coins, i.e. `log10(BaseUnit)` rounded down.
+	///
+	/// E.g., a `BaseUnit` of 1_000_000 means that balances are displayed with 6 decimals.
+	///
+	/// **Weight:**
+	/// - complexity: `O(log(BaseUnit))`
+	/// - DB access: none
+	pub fn decimals() -> u32 {
+		let mut base_unit = T::BaseUnit::get();
+		let mut decimals = 0;
+		while base_unit >= 10 {
+			base_unit /= 10;
+			decimals += 1;
+		}
+		decimals
+	}
+
 	/// Transfer `amount` of Coins from one account to another.
 	///
+	/// See `do_transfer` for details.
+	fn transfer_from_to(from: &T::AccountId, to: &T::AccountId, amount: Coins) -> DispatchResult {
+		Self::do_transfer(from, to, amount).map(|_balances| ())
+	}
+
+	/// Transfer `amount` of Coins from one account to another and return the updated balances
+	/// of the sender and the receiver.
+	///
+	/// Fails with `BelowExistentialDeposit` if the sender would be left with a nonzero balance
+	/// below `ExistentialDeposit`. A sender left with no balance is removed from storage.
+	/// Fails with `AccountFrozen` if the sender or the receiver is frozen.
+	/// A transfer to the sender itself is checked the same way but leaves the balance unchanged.
+	///
 	/// **Weight:**
 	/// - complexity: `O(1)`
-	/// - DB access: 2 storage map reads + 2 storage map writes
-	fn transfer_from_to(from: &T::AccountId, to: &T::AccountId, amount: Coins) -> DispatchResult {
+	/// - DB access: 4 storage map reads + 2 storage map writes
+	pub fn do_transfer(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: Coins,
+	) -> Result<(Coins, Coins), DispatchError> {
+		Self::ensure_not_frozen(from)?;
+		Self::ensure_not_frozen(to)?;
 		let from_balance = Self::get_balance(from);
 		let updated_from_balance = from_balance
 			.checked_sub(amount)
 			.ok_or(Error::<T>::InsufficientBalance)?;
+		ensure!(
+			updated_from_balance == 0 || updated_from_balance >= T::ExistentialDeposit::get(),
+			Error::<T>::BelowExistentialDeposit
+		);
+		// sender and receiver share the same balance --> nothing to move
+		if from == to {
+			return Ok((from_balance, from_balance));
+		}
 		let receiver_balance = Self::get_balance(&to);
 		let updated_to_balance = receiver_balance
 			.checked_add(amount)
@@ -606,66 +2103,534 @@ impl<T: Trait> Module<T> {
 		// ↓ update ↓
 
 		// reduce from's balance
-		<Balance<T>>::insert(&from, updated_from_balance);
+		Self::set_balance(&from, updated_from_balance);
 		// increase receiver's balance
 		<Balance<T>>::insert(&to, updated_to_balance);
 
+		Ok((updated_from_balance, updated_to_balance))
+	}
+
+	/// Return the number of bid-related calls `who` made in the current block.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 storage map read
+	fn bid_actions_in_block(who: &T::AccountId) -> u32 {
+		Self::bid_actions(<system::Module<T>>::block_number(), who)
+	}
+
+	/// Ensure that `who` can make another bid-related call in the current block (see `MaxBidActionsPerBlock`).
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 storage map read
+	fn ensure_not_rate_limited(who: &T::AccountId) -> DispatchResult {
+		ensure!(
+			Self::bid_actions_in_block(who) < T::MaxBidActionsPerBlock::get(),
+			Error::<T>::RateLimited
+		);
+		Ok(())
+	}
+
+	/// Count a bid-related call of `who` in the current block.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 storage map read and write
+	fn note_bid_action(who: &T::AccountId) {
+		<BidActions<T>>::mutate(<system::Module<T>>::block_number(), who, |count| {
+			*count = count.saturating_add(1)
+		});
+	}
+
+	/// Ensure that `who` is not frozen (see `freeze_account`).
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 storage map read
+	fn ensure_not_frozen(who: &T::AccountId) -> DispatchResult {
+		ensure!(!Self::is_frozen(who), Error::<T>::AccountFrozen);
+		Ok(())
+	}
+
+	/// Add `amount` Coins to the balance for `account`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 write to balance storage map
+	fn add_balance(account: &T::AccountId, amount: Coins) {
+		<Balance<T>>::mutate(account, |b: &mut u64| {
+			*b = b.saturating_add(amount);
+			*b
+		});
+	}
+
+	/// Remove `amount` Coins from the balance of `account`.
+	///
+	/// Removes the storage entry if the balance reaches zero.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and 1 write to balance storage map
+	fn remove_balance(account: &T::AccountId, amount: Coins) -> DispatchResult {
+		let remaining = Self::get_balance(account)
+			.checked_sub(amount)
+			.ok_or(Error::<T>::InsufficientBalance)?;
+		Self::set_balance(account, remaining);
+		Ok(())
+	}
+
+	/// Set the balance of `account` to `amount`, removing the storage entry if it is zero.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 storage map write
+	fn set_balance(account: &T::AccountId, amount: Coins) {
+		if amount == 0 {
+			<Balance<T>>::remove(account);
+		} else {
+			<Balance<T>>::insert(account, amount);
+		}
+	}
+
+	/// Move `amount` Coins of `who` from the free to the reserved balance.
+	///
+	/// Fails with `InsufficientBalance` if the free balance is lower than `amount`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 2 reads and writes
+	fn reserve(who: &T::AccountId, amount: Coins) -> DispatchResult {
+		let free = Self::get_balance(who)
+			.checked_sub(amount)
+			.ok_or(Error::<T>::InsufficientBalance)?;
+		let reserved = Self::reserved_balance(who)
+			.checked_add(amount)
+			.ok_or(Error::<T>::BalanceOverflow)?;
+		// ↑ verify ↑
+		// ↓ update ↓
+		Self::set_balance(who, free);
+		Self::set_reserved(who, reserved);
+		Ok(())
+	}
+
+	/// Move up to `amount` Coins of `who` from the reserved back to the free balance.
+	///
+	/// Returns the amount of Coins unreserved.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 2 reads and writes
+	fn unreserve(who: &T::AccountId, amount: Coins) -> Coins {
+		let reserved = Self::reserved_balance(who);
+		let unreserved = min(amount, reserved);
+		Self::set_reserved(who, reserved - unreserved);
+		Self::add_balance(who, unreserved);
+		unreserved
+	}
+
+	/// Remove up to `amount` Coins from the reserved balance of `who` without returning them.
+	///
+	/// Used when reserved Coins are burned. Returns the amount of Coins removed.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write
+	fn burn_reserved(who: &T::AccountId, amount: Coins) -> Coins {
+		let reserved = Self::reserved_balance(who);
+		let burned = min(amount, reserved);
+		Self::set_reserved(who, reserved - burned);
+		burned
+	}
+
+	/// Set the reserved balance of `who` to `amount`, removing the storage entry if it is zero.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 storage map write
+	fn set_reserved(who: &T::AccountId, amount: Coins) {
+		if amount == 0 {
+			<Reserved<T>>::remove(who);
+		} else {
+			<Reserved<T>>::insert(who, amount);
+		}
+	}
+
+	// ------------------------------------------------------------
+	// bids
+
+	/// Return the minimum percentage to pay for a bond.
+	///
+	/// Uses the value set by governance if present and falls back to `MinimumBondPrice`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read
+	pub fn minimum_bond_price() -> Perbill {
+		Self::minimum_bond_price_override().unwrap_or_else(T::MinimumBondPrice::get)
+	}
+
+	/// Return the price the Coins are pegged to.
+	///
+	/// Uses the value set by governance if present and falls back to `BaseUnit`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read
+	pub fn target_unit() -> Coins {
+		Self::target_unit_override().unwrap_or_else(T::BaseUnit::get)
+	}
+
+	/// Return the number of blocks between supply adjustments.
+	///
+	/// Uses the value set by governance if present and falls back to `AdjustmentFrequency`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read
+	pub fn adjustment_frequency() -> T::BlockNumber {
+		Self::adjustment_frequency_override().unwrap_or_else(T::AdjustmentFrequency::get)
+	}
+
+	/// Validate `bid`, reserve its payment and add it to the bidding queue.
+	///
+	/// Fails with `PaymentExceedsLimit` if `max_payment` is given and the payment for the bid is greater.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)`
+	///   - `B` being the number of bids in the bidding auction, limited to `MaximumBids`
+	/// - DB access:
+	///   - read and write bids from and to DB
+	///   - 1 DB storage map write to pay the bid
+	///   - 1 potential DB storage map write to refund evicted bid
+	fn place_bid(bid: Bid<T::AccountId, T::BlockNumber>, max_payment: Option<Coins>) -> DispatchResult {
+		let (who, price, quantity) = (bid.account.clone(), bid.price, bid.quantity);
+		let payment = bid.payment();
+		Self::ensure_not_frozen(&who)?;
+		Self::ensure_not_rate_limited(&who)?;
+		Self::ensure_valid_bid(price, quantity)?;
+		if let Some(max_payment) = max_payment {
+			ensure!(payment <= max_payment, Error::<T>::PaymentExceedsLimit);
+		}
+		ensure!(
+			Self::bid_count_of(&who) < T::MaxBidsPerAccount::get(),
+			Error::<T>::TooManyBidsForAccount
+		);
+		Self::ensure_bids_fit(iter::once(price))?;
+
+		// ↑ verify ↑
+		Self::reserve(&who, payment)?;
+		// ↓ update ↓
+		Self::add_bid(bid);
+		Self::note_bid_action(&who);
+		Self::deposit_event(RawEvent::NewBid(who, price, quantity));
+
+		Ok(())
+	}
+
+	/// Construct a transient storage adapter for the bids priority queue.
+	fn bids_transient() -> BoundedPriorityQueue<Bid<T::AccountId, T::BlockNumber>, <Self as Store>::BondBids, T::MaximumBids>
+	{
+		BoundedPriorityQueue::<Bid<T::AccountId, T::BlockNumber>, <Self as Store>::BondBids, T::MaximumBids>::new()
+	}
+
+	/// Return the bids in the queue sorted by price from lowest to highest.
+	///
+	/// The highest bid is last (the next one to be converted to a bond).
+	///
+	/// **Weight:**
+	/// - complexity: `O(B log B)` with `B` being the amount of bids
+	/// - DB access: read `B` bids
+	pub fn bond_bids() -> Vec<Bid<T::AccountId, T::BlockNumber>> {
+		Self::bids_transient().into_sorted_vec()
+	}
+
+	/// Return the `n` highest bids in the queue sorted by price from highest to lowest.
+	///
+	/// Only the returned bids are sorted, which makes this cheaper than `bond_bids` for small `n`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B + n log B)` with `B` being the amount of bids
+	/// - DB access: read `B` bids
+	pub fn top_bids(n: usize) -> Vec<Bid<T::AccountId, T::BlockNumber>> {
+		let mut heap = BinaryHeap::from(<BondBids<T>>::get());
+		let mut top = Vec::with_capacity(min(n, heap.len()));
+		while top.len() < n {
+			match heap.pop() {
+				Some(bid) => top.push(bid),
+				None => break,
+			}
+		}
+		top
+	}
+
+	/// Return the average price of the bids in the queue weighted by their quantity.
+	///
+	/// The average is rounded down. Returns `None` if there are no bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the amount of bids
+	/// - DB access: read `B` bids
+	pub fn average_bid_price() -> Option<Perbill> {
+		let (weighted, total_quantity) = <BondBids<T>>::get().iter().try_fold(
+			(0u128, 0u128),
+			|(weighted, total_quantity), bid| {
+				let weighted_price = u128::from(bid.price.deconstruct()).checked_mul(u128::from(bid.quantity))?;
+				Some((
+					weighted.checked_add(weighted_price)?,
+					total_quantity.checked_add(u128::from(bid.quantity))?,
+				))
+			},
+		)?;
+		if total_quantity == 0 {
+			return None;
+		}
+		// the average of prices is a valid price, so it fits into `u32`
+		Some(Perbill::from_parts((weighted / total_quantity) as u32))
+	}
+
+	/// Ensure that a bid at `price` for `quantity` Coins is valid.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read of the minimum bond price override
+	fn ensure_valid_bid(price: Perbill, quantity: Coins) -> DispatchResult {
+		ensure!(price <= Perbill::from_percent(100), Error::<T>::BondPriceOver100Percent);
+		ensure!(price > Self::minimum_bond_price(), Error::<T>::BondPriceTooLow);
+		ensure!(quantity >= T::MinimumBidQuantity::get(), Error::<T>::BondQuantityTooLow);
+		// the payment is rounded and could be zero for tiny prices
+		ensure!(price * quantity > 0, Error::<T>::ZeroAmount);
+		Ok(())
+	}
+
+	/// Return the bids of `who`, ordered from lowest to highest price.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B log B)` with `B` being the amount of bids
+	/// - DB access: read `B` bids
+	pub fn bids_of(who: &T::AccountId) -> Vec<Bid<T::AccountId, T::BlockNumber>> {
+		Self::bond_bids()
+			.into_iter()
+			.filter(|bid| bid.account == *who)
+			.collect()
+	}
+
+	/// Ensure that bids at `prices` (added in order) all make it into the bidding queue.
+	///
+	/// If the queue is full, a bid only evicts the lowest bid if its price is strictly higher.
+	/// Fails with `BidQueueFull` otherwise, so that bidding at the lowest price cannot be used
+	/// to churn the queue.
+	/// With `BidEvictionPolicy::RejectNew` a full queue rejects every new bid.
+	///
+	/// **Weight:**
+	/// - complexity: `O((B + N) log B)`
+	///   - `B` being the amount of bids
+	///   - `N` being the amount of new bids
+	/// - DB access: read `B` bids
+	fn ensure_bids_fit<I>(prices: I) -> DispatchResult
+	where
+		I: Iterator<Item = Perbill>,
+	{
+		Self::ensure_bids_fit_after_cancelling(prices, |_bid| false)
+	}
+
+	/// Ensure that bids at `prices` fit into the bidding queue once the bids for which
+	/// `cancelled` returns true are removed (see `ensure_bids_fit`).
+	///
+	/// **Weight:**
+	/// - complexity: `O((B + N) log B)`
+	///   - `B` being the amount of bids
+	///   - `N` being the amount of new bids
+	/// - DB access: read `B` bids
+	fn ensure_bids_fit_after_cancelling<I, F>(prices: I, cancelled: F) -> DispatchResult
+	where
+		I: Iterator<Item = Perbill>,
+		F: Fn(&Bid<T::AccountId, T::BlockNumber>) -> bool,
+	{
+		let max_bids = T::MaximumBids::get();
+		let may_evict = T::BidEvictionPolicy::get() == BidEvictionPolicy::EvictLowest;
+		// min-heap of the prices in the queue
+		let mut queue: BinaryHeap<Reverse<Perbill>> = <BondBids<T>>::get()
+			.iter()
+			.filter(|bid| !cancelled(bid))
+			.map(|bid| Reverse(bid.price))
+			.collect();
+		for price in prices {
+			if (queue.len() as u64) < max_bids {
+				queue.push(Reverse(price));
+				continue;
+			}
+			match queue.peek() {
+				Some(Reverse(lowest)) if may_evict && price > *lowest => {
+					queue.pop();
+					queue.push(Reverse(price));
+				}
+				_ => return Err(Error::<T>::BidQueueFull.into()),
+			}
+		}
 		Ok(())
 	}
 
-	/// Add `amount` Coins to the balance for `account`.
+	/// Validate `bids` of `who` and return the total payment for them.
 	///
 	/// **Weight:**
-	/// - complexity: `O(1)`
-	/// - DB access: 1 write to balance storage map
-	fn add_balance(account: &T::AccountId, amount: Coins) {
-		<Balance<T>>::mutate(account, |b: &mut u64| {
-			*b = b.saturating_add(amount);
-			*b
-		});
+	/// - complexity: `O(N)` with `N` being the number of bids
+	/// - DB access: 1 read of the minimum bond price override
+	fn total_payment_for(who: &T::AccountId, bids: &[(Perbill, Coins)]) -> Result<Coins, DispatchError> {
+		let mut total_payment: Coins = 0;
+		for (price, quantity) in bids.iter() {
+			Self::ensure_valid_bid(*price, *quantity)?;
+			let bid = Bid::<T::AccountId, T::BlockNumber>::new(who.clone(), *price, *quantity);
+			total_payment = total_payment
+				.checked_add(bid.payment())
+				.ok_or(Error::<T>::GenericOverflow)?;
+		}
+		Ok(total_payment)
 	}
 
-	/// Remove `amount` Coins from the balance of `account`.
+	/// Add the already paid `bids` of `who` to the bidding queue.
 	///
 	/// **Weight:**
-	/// - complexity: `O(1)`
-	/// - DB access: 1 write to balance storage map
-	fn remove_balance(account: &T::AccountId, amount: Coins) -> DispatchResult {
-		<Balance<T>>::try_mutate(&account, |b: &mut u64| -> DispatchResult {
-			*b = b.checked_sub(amount).ok_or(Error::<T>::InsufficientBalance)?;
-			Ok(())
-		})
+	/// - complexity: `O(N log B)`
+	///   - `N` being the number of bids
+	///   - `B` being the amount of bids in the queue
+	/// - DB access:
+	///   - read and write bids from and to DB
+	///   - up to `N` potential DB storage map writes to refund evicted bids
+	fn push_bids(who: &T::AccountId, bids: Vec<(Perbill, Coins)>, total_payment: Coins) {
+		Self::lock_bid_payment(total_payment);
+		let mut queue = Self::bids_transient();
+		for (price, quantity) in bids {
+			queue
+				.push(Self::sequenced(Bid::new(who.clone(), price, quantity)))
+				.map(|to_refund| Self::refund_bid(&to_refund));
+			Self::deposit_event(RawEvent::NewBid(who.clone(), price, quantity));
+		}
 	}
 
-	// ------------------------------------------------------------
-	// bids
+	/// Return the number of bids of `who` in the queue.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the amount of bids
+	/// - DB access: read `B` bids
+	fn bid_count_of(who: &T::AccountId) -> u64 {
+		<BondBids<T>>::get().iter().filter(|bid| bid.account == *who).count() as u64
+	}
 
-	/// Construct a transient storage adapter for the bids priority queue.
-	fn bids_transient() -> BoundedPriorityQueue<Bid<T::AccountId>, <Self as Store>::BondBids, T::MaximumBids>
-	{
-		BoundedPriorityQueue::<Bid<T::AccountId>, <Self as Store>::BondBids, T::MaximumBids>::new()
+	/// Return the amount of Coins `who` has locked in bids.
+	///
+	/// Equal to the reserved balance of `who`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read
+	pub fn locked_in_bids(who: &T::AccountId) -> Coins {
+		Self::reserved_balance(who)
 	}
 
 	/// Add a bid to the queue.
 	///
 	/// **Weight:**
 	/// - complexity: `O(B)` with `B` being the amount of bids
+	///   - `O(log B)` for the insertion itself, but reading and writing the queue is `O(B)`
 	/// - DB access:
 	///   - read and write `B` bids
 	///   - potentially call 1 `refund_bid`
-	fn add_bid(bid: Bid<T::AccountId>) {
+	fn add_bid(bid: Bid<T::AccountId, T::BlockNumber>) {
+		Self::lock_bid_payment(bid.payment());
 		Self::bids_transient()
-			.push(bid)
+			.push(Self::sequenced(bid))
 			.map(|to_refund| Self::refund_bid(&to_refund));
 	}
 
-	/// Refund the Coins payed for `bid` to the account that bid.
+	/// Assign the next sequence number and the expiration to `bid`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and write
+	fn sequenced(mut bid: Bid<T::AccountId, T::BlockNumber>) -> Bid<T::AccountId, T::BlockNumber> {
+		bid.sequence = <NextBidSequence>::mutate(|next| {
+			let sequence = *next;
+			*next = next.wrapping_add(1);
+			sequence
+		});
+		bid.expiration = Self::bid_expiration();
+		bid
+	}
+
+	/// Return the expiration block of a bid placed in the current block.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read of the block number
+	fn bid_expiration() -> T::BlockNumber {
+		<system::Module<T>>::block_number().saturating_add(T::BidExpirationPeriod::get())
+	}
+
+	/// Remove all expired bids from the bidding queue and refund them.
+	///
+	/// Returns the number of removed bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the amount of bids
+	/// - DB access:
+	///   - read `B` bids, write them only if any expired
+	///   - call `refund_bid` for every expired bid
+	fn purge_expired_bids() -> u32 {
+		let now = <system::Module<T>>::block_number();
+		let is_expired = |bid: &Bid<T::AccountId, T::BlockNumber>| now >= bid.expiration;
+		if !<BondBids<T>>::get().iter().any(is_expired) {
+			return 0;
+		}
+		let mut expired = 0;
+		Self::bids_transient().retain(|bid| {
+			if is_expired(bid) {
+				Self::refund_bid(bid);
+				Self::deposit_event(RawEvent::BidExpired(bid.account.clone(), bid.price, bid.quantity));
+				expired += 1;
+				return false;
+			}
+			true
+		});
+		expired
+	}
+
+	/// Add `payment` to the running total of Coins locked in bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and 1 write
+	fn lock_bid_payment(payment: Coins) {
+		<LockedInBids>::mutate(|locked| *locked = locked.saturating_add(payment));
+	}
+
+	/// Remove `payment` from the running total of Coins locked in bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read and 1 write
+	fn unlock_bid_payment(payment: Coins) {
+		<LockedInBids>::mutate(|locked| *locked = locked.saturating_sub(payment));
+	}
+
+	/// Return the amount of Coins in circulation, i.e. the coin supply without the Coins locked
+	/// (reserved) in bids.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 2 reads
+	pub fn circulating_supply() -> Coins {
+		Self::coin_supply().saturating_sub(Self::total_locked_in_bids())
+	}
+
+	/// Refund the Coins payed for `bid` to the account that bid by unreserving them.
 	///
 	/// **Weight:**
 	/// - complexity: `O(1)`
-	/// - DB access: 1 write
-	fn refund_bid(bid: &Bid<T::AccountId>) {
-		Self::add_balance(&bid.account, bid.payment());
+	/// - DB access: 2 reads and writes
+	fn refund_bid(bid: &Bid<T::AccountId, T::BlockNumber>) {
+		Self::unlock_bid_payment(bid.payment());
+		Self::unreserve(&bid.account, bid.payment());
 		Self::deposit_event(RawEvent::RefundedBid(bid.account.clone(), bid.payment()));
 	}
 
@@ -678,9 +2643,9 @@ impl<T: Trait> Module<T> {
 	///   - call `refund_bid` up to `B` times
 	fn cancel_bids<F>(cancel_for: F)
 	where
-		F: Fn(&Bid<T::AccountId>) -> bool,
+		F: Fn(&Bid<T::AccountId, T::BlockNumber>) -> bool,
 	{
-		let mut bids = Self::bond_bids();
+		let mut bids = Self::bids_transient();
 
 		bids.retain(|b| {
 			if cancel_for(b) {
@@ -689,25 +2654,29 @@ impl<T: Trait> Module<T> {
 			}
 			true
 		});
-
-		<BondBids<T>>::put(bids);
 	}
 
 	/// Tries to contract the supply by `amount` by converting bids to bonds.
 	///
-	/// Note: Could contract the supply by less than `amount` if there are not enough bids.
+	/// Returns the amount of Coins the supply was actually contracted by.
+	///
+	/// Note: Could contract the supply by less than `amount` if there are not enough bids
+	/// or the bonds queue is full (see `MaximumBonds`). In the latter case the bids that would
+	/// have covered the rest of `amount` are refunded.
+	/// If there are no bids at all, emits `ContractionFailedNoBids` and returns without contracting.
+	/// Fails with `CoinSupplyUnderflow` if the supply would drop below `MinimumSupply`, even without bids.
 	///
 	/// **Weight:**
 	/// - complexity: `O(BI + BO + C)`
 	///   - `BI` being the number of bids in the bidding auction, limited to `MaximumBids`
-	///   - `BO` being the number of newly created bonds, limited to `BI`
+	///   - `BO` being the number of newly created bonds, limited to `BI` and `MaximumBonds`
 	///   - `C` being a constant amount of storage reads and writes for coin supply and bonds queue bounds bookkeeping
 	/// - DB access:
 	///   - 1 write for `coin_supply`
 	///   - read and write bids
 	///   - write `BO` newly created bonds + read and write bonds queue bounds
 	///   - potentially refund up to `BI` bids
-	fn contract_supply(coin_supply: Coins, amount: Coins) -> DispatchResult {
+	fn contract_supply(coin_supply: Coins, amount: Coins) -> Result<Coins, DispatchError> {
 		// Checking whether coin supply would underflow.
 		let remaining_supply = coin_supply
 			.checked_sub(amount)
@@ -715,40 +2684,73 @@ impl<T: Trait> Module<T> {
 		if remaining_supply < T::MinimumSupply::get() {
 			return Err(DispatchError::from(Error::<T>::CoinSupplyUnderflow));
 		}
-		// ↑ verify ↑
 		let mut bids = Self::bids_transient();
+		if bids.is_empty() {
+			native::info!("no bids to contract the supply by: {}", amount);
+			Self::deposit_event(RawEvent::ContractionFailedNoBids(amount));
+			return Ok(0);
+		}
+		// ↑ verify ↑
 		let mut remaining = amount;
 		let mut new_bonds = VecDeque::new();
+		let bond_capacity = T::MaximumBonds::get().saturating_sub(Self::bonds_range().1) as usize;
 		// ↓ update ↓
-		while remaining > 0 && !bids.is_empty() {
+		while remaining > 0 && !bids.is_empty() && new_bonds.len() < bond_capacity {
 			let mut bid = bids
 				.pop()
 				.expect("checked whether queue is empty on previous line; qed");
-			// the current bid can cover all the remaining contraction
-			if bid.payment() >= remaining {
+			let payment = bid.payment();
+			// The current bid can cover all the remaining contraction with some quantity left over.
+			// Note: A bid with `payment == remaining` is converted entirely by the `else` branch because
+			// `remove_coins` would underflow if `payment` was rounded up.
+			if payment > remaining {
 				match bid.remove_coins(remaining) {
-					Err(_e) => {
-						native::warn!("unable to remove coins from bid --> refunding bid: {:?}", bid);
+					Err(e) => {
+						// unreachable because `remaining < payment` always buys less than the bid's quantity
+						native::error!("unable to remove coins from bid ({:?}) --> refunding bid: {:?}", e, bid);
 						Self::refund_bid(&bid);
 					}
 					Ok(removed_quantity) => {
-						new_bonds.push_back(Self::new_bond(bid.account.clone(), removed_quantity));
+						Self::unlock_bid_payment(payment);
+						// burn exactly the converted Coins, the reserve for the remaining bid is kept
+						Self::burn_reserved(&bid.account, remaining);
+						new_bonds.push_back(Self::new_bond(bid.account.clone(), removed_quantity, bid.price));
 						// re-add bid with reduced amount
 						if bid.quantity > 0 {
+							Self::deposit_event(RawEvent::BidPartiallyConverted(
+								bid.account.clone(),
+								removed_quantity,
+								bid.quantity,
+							));
+							Self::lock_bid_payment(bid.payment());
 							bids.push(bid).map(|to_refund| Self::refund_bid(&to_refund));
 						}
 						remaining = 0;
 					}
 				}
 			} else {
-				let payment = bid.payment();
+				Self::unlock_bid_payment(payment);
+				Self::burn_reserved(&bid.account, payment);
 				let Bid {
-					account, quantity, ..
+					account,
+					price,
+					quantity,
+					..
 				} = bid;
-				new_bonds.push_back(Self::new_bond(account, quantity));
+				new_bonds.push_back(Self::new_bond(account, quantity, price));
 				remaining -= payment;
 			}
 		}
+		// The bonds queue is full if Coins remain while there are still bids, so refund
+		// the bids that could not be converted instead of keeping them locked.
+		let mut unconverted = remaining;
+		while unconverted > 0 && !bids.is_empty() {
+			let bid = bids
+				.pop()
+				.expect("checked whether queue is empty on previous line; qed");
+			unconverted = unconverted.saturating_sub(bid.payment());
+			Self::refund_bid(&bid);
+		}
 		debug_assert!(
 			remaining <= amount,
 			"remaining is never greater than the original amount"
@@ -766,47 +2768,184 @@ impl<T: Trait> Module<T> {
 				bond.expiration,
 			));
 		}
-		let mut bonds = Self::bonds_transient();
-		for bond in new_bonds {
-			bonds.push_back(bond);
+		if let Some(last) = new_bonds.back() {
+			let total_payout = new_bonds
+				.iter()
+				.fold(0 as Coins, |total, bond| total.saturating_add(bond.payout));
+			Self::deposit_event(RawEvent::BondsCreated(
+				new_bonds.len() as u32,
+				total_payout,
+				last.expiration,
+			));
 		}
+		Self::push_bonds(new_bonds).expect("number of new bonds is limited by the bond capacity; qed");
 		<CoinSupply>::put(new_supply);
+		<TotalBurned>::mutate(|total| *total = total.saturating_add(burned));
 		native::info!("contracted supply by: {}", burned);
 		Self::deposit_event(RawEvent::ContractedSupply(burned));
-		Ok(())
+		Self::deposit_event(RawEvent::SupplyChanged(coin_supply, new_supply));
+		if burned < amount {
+			Self::deposit_event(RawEvent::ContractionShortfall(amount, burned));
+		}
+		if burned > 0 {
+			T::OnSupplyChange::on_contract(burned);
+		}
+		Ok(burned)
 	}
 
 	// ------------------------------------------------------------
 	// bonds
 
-	/// Create a new bond for the given `account` with the given `payout`.
+	/// Create a new bond for the given `account` with the given `payout`, originating from a bid at `price`.
 	///
-	/// Expiration is calculated based on the current `block_number` and the configured
-	/// `ExpirationPeriod`.
-	fn new_bond(account: T::AccountId, payout: Coins) -> Bond<T::AccountId, T::BlockNumber> {
-		let expiration = <system::Module<T>>::block_number() + T::ExpirationPeriod::get();
+	/// The `BondBonus` is added on top of `payout`.
+	/// Expiration is calculated by the configured `BondExpiration` strategy based on the current
+	/// `block_number` and the length of the bonds queue.
+	fn new_bond(account: T::AccountId, payout: Coins, price: Perbill) -> Bond<T::AccountId, T::BlockNumber> {
+		let expiration = T::BondExpiration::expiration(<system::Module<T>>::block_number(), Self::bonds_range().1);
+		let bonus = T::BondBonus::get() * payout;
 		Bond {
 			account,
-			payout,
+			payout: payout.saturating_add(bonus),
 			expiration,
+			price,
+		}
+	}
+
+	/// Return all bonds in queue order (i.e., the first one will be paid out first).
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the number of bonds
+	/// - DB access: 1 read of the bonds range + read `B` bonds
+	pub fn bonds() -> Vec<Bond<T::AccountId, T::BlockNumber>> {
+		BondsQueue::<T>::new_readonly().iter().collect()
+	}
+
+	/// Return the number of bonds in the bonds queue.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read of the bonds range
+	pub fn bond_count() -> u32 {
+		let (_start, length) = Self::bonds_range();
+		length.into()
+	}
+
+	/// Return the aggregated state of the pallet, e.g. for an RPC extension.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the number of bids
+	/// - DB access: 4 reads + read `B` bids
+	pub fn state_snapshot() -> StablecoinState {
+		StablecoinState {
+			coin_supply: Self::coin_supply(),
+			minimum_supply: T::MinimumSupply::get(),
+			bid_count: <BondBids<T>>::get().len() as u64,
+			bond_count: Self::bond_count(),
+			minimum_bond_price: Self::minimum_bond_price(),
+			has_shares: Self::share_supply() > 0,
+		}
+	}
+
+	/// Return all bonds of `who` together with their index in the bonds queue.
+	///
+	/// The bonds are returned in queue order (i.e., the first one will be paid out first).
+	///
+	/// **Weight:**
+	/// - complexity: `O(A)` with `A` being the number of bonds of `who`
+	/// - DB access: 1 read of the account's bond indices + read `A` bonds
+	pub fn bonds_of(who: &T::AccountId) -> Vec<(BondIndex, Bond<T::AccountId, T::BlockNumber>)> {
+		Self::bond_indices_of(who)
+			.into_iter()
+			.map(|index| (index, Self::get_bond(index)))
+			// defensive: skip stale indices that were overwritten by another bond
+			.filter(|(_, bond)| bond.account == *who)
+			.collect()
+	}
+
+	/// Push `new_bonds` onto the back of the bonds queue and record their indices
+	/// for their accounts.
+	///
+	/// Refuses to add any bond with `TooManyBonds` if the queue would exceed `MaximumBonds`.
+	///
+	/// **Weight:**
+	/// - complexity: `O(N)` with `N` being the number of new bonds
+	/// - DB access:
+	///   - write `N` bonds + read and write bonds queue bounds
+	///   - read and write `N` account bond indices
+	fn push_bonds<I>(new_bonds: I) -> DispatchResult
+	where
+		I: IntoIterator<Item = Bond<T::AccountId, T::BlockNumber>>,
+		I::IntoIter: ExactSizeIterator,
+	{
+		let new_bonds = new_bonds.into_iter();
+		let mut bonds = Self::bonds_transient();
+		let count = (bonds.len() as usize).saturating_add(new_bonds.len());
+		ensure!(count <= T::MaximumBonds::get() as usize, Error::<T>::TooManyBonds);
+		// ↑ verify ↑
+		// ↓ update ↓
+		for bond in new_bonds {
+			let account = bond.account.clone();
+			let index = bonds.push_back(bond);
+			<BondsByAccount<T>>::mutate(&account, |indices| indices.push(index));
+		}
+		Ok(())
+	}
+
+	/// Remove the index of the oldest bond of `account` from the account's bond indices.
+	///
+	/// Bonds are only removed from the front of the queue (because they were paid out or
+	/// expired) so the removed bond is always the oldest one recorded for its account.
+	///
+	/// **Weight:**
+	/// - complexity: `O(A)` with `A` being the number of bonds of `account`
+	/// - DB access: 1 read and write of the account's bond indices
+	fn unindex_oldest_bond(account: &T::AccountId) {
+		let mut indices = Self::bond_indices_of(account);
+		if !indices.is_empty() {
+			indices.remove(0);
+		}
+		if indices.is_empty() {
+			<BondsByAccount<T>>::remove(account);
+		} else {
+			<BondsByAccount<T>>::insert(account, indices);
+		}
+	}
+
+	/// Remove up to `max_expirations` expired bonds from the front of the bonds queue.
+	///
+	/// Bonds are pushed in the order of their expiration so only the front needs to be checked.
+	/// Returns the number of removed bonds.
+	///
+	/// **Weight:**
+	/// - complexity: `O(E)` with `E` being the number of expired bonds, limited to `max_expirations`
+	/// - DB access:
+	///   - read and remove `E + 1` bonds + read and write bonds queue bounds
+	///   - potentially write back 1 bond
+	///   - read and write `E` account bond indices
+	fn purge_expired_bonds(max_expirations: u32) -> u32 {
+		let now = <system::Module<T>>::block_number();
+		let mut bonds = Self::bonds_transient();
+		let mut expired = 0;
+		while expired < max_expirations {
+			match bonds.peek_front() {
+				Some(bond) if now >= bond.expiration => {
+					bonds.pop_front();
+					Self::unindex_oldest_bond(&bond.account);
+					Self::deposit_event(RawEvent::BondExpired(bond.account, bond.payout));
+					expired += 1;
+				}
+				_ => break,
+			}
 		}
+		expired
 	}
 
 	/// Create a new transient storage adapter that manages the bonds.
 	///
 	/// Allows pushing and popping on a ringbuffer without managing the storage details.
-	fn bonds_transient() -> BoundedDeque<
-		Bond<T::AccountId, T::BlockNumber>,
-		<Self as Store>::BondsRange,
-		<Self as Store>::Bonds,
-		BondIndex,
-	> {
-		BoundedDeque::<
-			Bond<T::AccountId, T::BlockNumber>,
-			<Self as Store>::BondsRange,
-			<Self as Store>::Bonds,
-			BondIndex,
-		>::new()
+	fn bonds_transient() -> BondsQueue<T> {
+		BondsQueue::<T>::new()
 	}
 
 	// ------------------------------------------------------------
@@ -815,7 +2954,10 @@ impl<T: Trait> Module<T> {
 	/// Expand the supply by `amount` by paying out bonds and shares.
 	///
 	/// Will first pay out bonds and only pay out shares if there are no remaining
-	/// bonds.
+	/// bonds. Bonds are paid out according to `BondPayoutMode`.
+	/// No bond receives more than `MaxBondPayoutPerExpansion`; capped bonds keep their
+	/// outstanding payout and their position in the queue. If bonds are still outstanding
+	/// because they were capped, the rest of `amount` is not minted at all.
 	///
 	/// **Weight:**
 	/// - complexity: `O(B + C + H)`
@@ -824,114 +2966,330 @@ impl<T: Trait> Module<T> {
 	///   - `H` being the complexity of `hand_out_coins`
 	/// - DB access:
 	///   - read bonds + read and write bonds queue bounds
-	///   - potentially write back 1 bond
-	///   - 1 write for `coin_supply` OR read shares and execute `hand_out_coins` which has DB accesses
+	///   - potentially write back `B` capped bonds
+	///   - 1 write for `coin_supply`
+	///   - potentially read shares and execute `distribute_coins` which has DB accesses
 	fn expand_supply(coin_supply: Coins, amount: Coins) -> DispatchResult {
-		// Checking whether the supply will overflow.
-		coin_supply
+		let max_supply = coin_supply
 			.checked_add(amount)
 			.ok_or(Error::<T>::CoinSupplyOverflow)?;
 		// ↑ verify ↑
 		let mut remaining = amount;
 		let mut bonds = Self::bonds_transient();
+		let max_payout = T::MaxBondPayoutPerExpansion::get();
+		let mut partially_paid = Vec::new();
 		// ↓ update ↓
 		while let Some(Bond {
 			account,
 			payout,
 			expiration,
+			price,
 		}) = if remaining > 0 { bonds.pop_front() } else { None }
 		{
+			// indexed again by `push_front_bonds` if the bond is put back
+			Self::unindex_oldest_bond(&account);
 			// bond has expired --> discard
 			if <system::Module<T>>::block_number() >= expiration {
 				Self::deposit_event(RawEvent::BondExpired(account, payout));
 				continue;
 			}
-			// bond does not cover the remaining amount --> resolve and continue
-			if payout <= remaining {
-				// this is safe because we are in the branch where remaining >= payout
-				remaining -= payout;
+			// pro-rata mode --> pay out all bonds with this expiration at once
+			if T::BondPayoutMode::get() == BondPayoutMode::ProRata {
+				let first = Bond {
+					account,
+					payout,
+					expiration,
+					price,
+				};
+				let (paid, group_partially_paid) =
+					Self::pay_out_bonds_pro_rata(&mut bonds, first, remaining, max_payout);
+				// safe because `pay_out_bonds_pro_rata` pays out at most `remaining`
+				remaining -= paid;
+				partially_paid.extend(group_partially_paid);
+				continue;
+			}
+			let paid = min(min(payout, max_payout), remaining);
+			// this is safe because `paid` is at most `remaining`
+			remaining -= paid;
+			// bond does not cover the remaining amount and is not capped --> resolve and continue
+			if paid == payout {
 				Self::add_balance(&account, payout);
-				Self::deposit_event(RawEvent::BondFulfilled(account, payout));
+				Self::deposit_event(RawEvent::BondFulfilled(account, payout, remaining));
 			}
-			// bond covers the remaining amount --> update and finish up
+			// bond covers the remaining amount or is capped --> update and continue with the next one
 			else {
-				// this is safe because we are in the else branch where payout > remaining
-				let payout = payout - remaining;
-				Self::add_balance(&account, remaining);
-				bonds.push_front(Bond {
-					account: account.clone(),
-					payout,
+				// this is safe because `paid` is at most `payout` and not equal to it
+				let outstanding = payout - paid;
+				Self::add_balance(&account, paid);
+				Self::deposit_event(RawEvent::BondPartiallyFulfilled(account.clone(), paid, outstanding));
+				partially_paid.push(Bond {
+					account,
+					payout: outstanding,
 					expiration,
+					price,
 				});
-				Self::deposit_event(RawEvent::BondPartiallyFulfilled(account, payout));
-				break;
 			}
 		}
-		// safe to do this late because of the test in the first line of the function
+		let bonds_outstanding = !partially_paid.is_empty();
+		Self::push_front_bonds(&mut bonds, partially_paid);
 		// safe to subtract remaining because we initialize it with amount and never increase it
-		let new_supply = coin_supply + amount - remaining;
-		native::info!("expanded supply by paying out bonds: {}", amount - remaining);
-		if remaining > 0 {
-			// relies on supply being updated in `hand_out_coins`
-			Self::hand_out_coins(&Self::shares(), remaining, new_supply)
-				.expect("coin supply overflow was checked at the beginning of function; qed");
+		let paid_to_bonds = amount - remaining;
+		let supply_after_bonds = max_supply - remaining;
+		<TotalBondPayouts>::mutate(|total| *total = total.saturating_add(paid_to_bonds));
+		native::info!("expanded supply by paying out bonds: {}", paid_to_bonds);
+		let new_supply = if remaining == 0 {
+			supply_after_bonds
+		} else if bonds_outstanding {
+			// capped bonds take precedence over shares
+			native::info!("bonds are capped, not minting {} coins", remaining);
+			supply_after_bonds
 		} else {
-			<CoinSupply>::put(new_supply);
+			// `distribute_coins` does not change anything if it fails
+			match Self::distribute_coins(&Self::shares(), remaining, supply_after_bonds) {
+				Ok(new_supply) => new_supply,
+				Err(e) => {
+					if e == DispatchError::from(Error::<T>::NoShares) {
+						native::warn!("no shares to hand out coins to, not minting {} coins", remaining);
+					} else {
+						native::error!("could not hand out coins: {:?}, not minting {} coins", e, remaining);
+					}
+					supply_after_bonds
+				}
+			}
+		};
+		// Safe because `new_supply` is at least `coin_supply`.
+		let expanded = new_supply - coin_supply;
+		<CoinSupply>::put(new_supply);
+		Self::deposit_event(RawEvent::ExpandedSupply(expanded));
+		Self::deposit_event(RawEvent::SupplyChanged(coin_supply, Self::coin_supply()));
+		if expanded > 0 {
+			T::OnSupplyChange::on_expand(expanded);
 		}
-		Self::deposit_event(RawEvent::ExpandedSupply(amount));
 		Ok(())
 	}
 
+	/// Pay out up to `amount` Coins to `first` and the bonds directly behind it in `bonds`
+	/// that expire at the same block.
+	///
+	/// If `amount` does not cover all of them it is shared proportionally to their payouts
+	/// (rounded down). No bond receives more than `max_payout`. The Coins lost to rounding or
+	/// capping go to the first bonds in queue order that can still take them.
+	/// Returns the amount paid out and the partially paid bonds, which the caller has to put
+	/// back with `push_front_bonds`.
+	///
+	/// `first` has to be unindexed by the caller already.
+	///
+	/// **Weight:**
+	/// - complexity: `O(G)` with `G` being the number of bonds with the same expiration, limited to `MaximumBonds`
+	/// - DB access:
+	///   - read and remove `G + 1` bonds, potentially write back 1 bond
+	///   - `G` writes of balances
+	///   - read and write `G` account bond indices
+	fn pay_out_bonds_pro_rata(
+		bonds: &mut BondsQueue<T>,
+		first: Bond<T::AccountId, T::BlockNumber>,
+		amount: Coins,
+		max_payout: Coins,
+	) -> (Coins, Vec<Bond<T::AccountId, T::BlockNumber>>) {
+		let expiration = first.expiration;
+		let mut group = Vec::new();
+		group.push(first);
+		while let Some(bond) = bonds.pop_front() {
+			if bond.expiration != expiration {
+				// back at the index it was popped from, so its account index stays valid
+				bonds.push_front(bond);
+				break;
+			}
+			Self::unindex_oldest_bond(&bond.account);
+			group.push(bond);
+		}
+		let total: u128 = group.iter().map(|bond| bond.payout as u128).sum();
+		let mut shares: Vec<Coins> = group
+			.iter()
+			.map(|bond| {
+				let share = if total <= amount as u128 {
+					bond.payout
+				} else {
+					// smaller than `bond.payout` because `amount < total`
+					(bond.payout as u128 * amount as u128 / total) as Coins
+				};
+				min(share, max_payout)
+			})
+			.collect();
+		// Hand out the Coins lost to rounding or capping in queue order.
+		// Without capping fewer Coins than bonds are lost to rounding and all of `amount` is paid out
+		// if `amount < total`.
+		let mut leftover = amount.saturating_sub(shares.iter().sum::<Coins>());
+		for (bond, share) in group.iter().zip(shares.iter_mut()) {
+			// safe because shares are at most `min(bond.payout, max_payout)`
+			let extra = min(min(bond.payout, max_payout) - *share, leftover);
+			*share += extra;
+			leftover -= extra;
+		}
+		let mut remaining = amount;
+		let mut partially_paid = Vec::new();
+		for (bond, share) in group.into_iter().zip(shares) {
+			// safe because the shares sum up to at most `amount`
+			remaining -= share;
+			let payout = bond.payout - share;
+			if payout == 0 {
+				Self::add_balance(&bond.account, share);
+				Self::deposit_event(RawEvent::BondFulfilled(bond.account, share, remaining));
+				continue;
+			}
+			if share > 0 {
+				Self::add_balance(&bond.account, share);
+				Self::deposit_event(RawEvent::BondPartiallyFulfilled(bond.account.clone(), share, payout));
+			}
+			partially_paid.push(Bond { payout, ..bond });
+		}
+		(amount - remaining, partially_paid)
+	}
+
+	/// Put partially paid bonds back in front of the bonds queue and record their new indices.
+	///
+	/// The bonds keep their order but their indices change if bonds between them were paid out.
+	/// They must have been popped from the front of the queue and unindexed before.
+	///
+	/// **Weight:**
+	/// - complexity: `O(B)` with `B` being the number of bonds to put back
+	/// - DB access:
+	///   - write `B` bonds
+	///   - read and write `B` account bond indices
+	fn push_front_bonds(bonds: &mut BondsQueue<T>, partially_paid: Vec<Bond<T::AccountId, T::BlockNumber>>) {
+		// push back in reverse order so the bonds keep their position in the queue
+		for bond in partially_paid.into_iter().rev() {
+			let account = bond.account.clone();
+			let index = bonds.push_front(bond);
+			<BondsByAccount<T>>::mutate(&account, |indices| indices.insert(0, index));
+		}
+	}
+
 	/// Hand out Coins to shareholders according to their number of shares.
 	///
-	/// Will hand out more Coins to shareholders at the beginning of the list
-	/// if the handout cannot be equal.
+	/// The order of `shares` determines who receives extra Coins, which is why the stored
+	/// shares are passed in the order of `Shareholders` (see `shares`).
+	///
+	/// Every shareholder receives its proportional part of `amount`,
+	/// rounded down. The Coins lost to rounding (fewer than there are shareholders) are handed
+	/// out one per shareholder, starting at the shareholder after the one who got the last extra
+	/// coin in the previous handout (see `HandoutCursor`).
 	///
 	/// **Weight:**
 	/// - complexity: `O(S + C)`
 	///   - `S` being `shares.len()` (the number of shareholders)
-	///   - `C` being a constant amount of storage reads and writes for coin supply
+	///   - `C` being a constant amount of storage reads and writes for coin supply and handout bookkeeping
 	/// - DB access:
 	///   - 1 write for `coin_supply`
+	///   - 1 read and write for the handout cursor
 	///   - `S` amount of writes
 	fn hand_out_coins(shares: &[(T::AccountId, u64)], amount: Coins, coin_supply: Coins) -> DispatchResult {
+		let new_supply = Self::distribute_coins(shares, amount, coin_supply)?;
+		<CoinSupply>::put(new_supply);
+		Ok(())
+	}
+
+	/// Distribute `amount` Coins to `shares` like `hand_out_coins`, but leave updating
+	/// `CoinSupply` to the caller.
+	///
+	/// Returns the new coin supply, i.e. `coin_supply` plus the Coins actually paid out.
+	/// Nothing is changed if an error is returned.
+	///
+	/// **Weight:**
+	/// - complexity: `O(S + C)`
+	///   - `S` being `shares.len()` (the number of shareholders)
+	///   - `C` being a constant amount of storage reads and writes for handout bookkeeping
+	/// - DB access:
+	///   - 1 read and write for the handout cursor
+	///   - `S` amount of writes
+	fn distribute_coins(
+		shares: &[(T::AccountId, u64)],
+		amount: Coins,
+		coin_supply: Coins,
+	) -> Result<Coins, DispatchError> {
 		// Checking whether the supply will overflow.
 		coin_supply
 			.checked_add(amount)
 			.ok_or(Error::<T>::CoinSupplyOverflow)?;
+		let (payouts, next_cursor) = Self::handout_for(shares, amount)?;
+		// cannot overflow because the payouts sum up to `amount`
+		let amount_payed = payouts.iter().sum::<Coins>();
+		let new_supply = coin_supply
+			.checked_add(amount_payed)
+			.ok_or(Error::<T>::CoinSupplyOverflow)?;
 		// ↑ verify ↑
-		let share_supply: u64 = shares.iter().map(|(_a, s)| s).sum();
-		let len = shares.len() as u64;
-		// No point in giving out less than 1 coin.
-		let coins_per_share = max(1, amount / share_supply);
-		let pay_extra = coins_per_share * len < amount;
-		let mut amount_payed = 0;
 		// ↓ update ↓
-		for (i, (acc, num_shares)) in shares.iter().enumerate() {
-			if amount_payed >= amount {
-				break;
-			}
-			let max_payout = amount - amount_payed;
-			let is_in_first_mod_len = (i as u64) < amount % len;
-			let extra_payout = if pay_extra && is_in_first_mod_len { 1 } else { 0 };
-			let payout = min(max_payout, num_shares * coins_per_share + extra_payout);
-			debug_assert!(
-				amount_payed + payout <= amount,
-				"amount payed out should be less or equal target amount"
-			);
+		for ((acc, _num_shares), payout) in shares.iter().zip(payouts) {
 			Self::add_balance(&acc, payout);
-			amount_payed += payout;
 		}
-		debug_assert!(
-			amount_payed == amount,
-			"amount payed out should equal target amount"
-		);
+		<HandoutCursor>::put(next_cursor);
+		<TotalMintedToShares>::mutate(|total| *total = total.saturating_add(amount_payed));
+		native::info!("expanded supply by handing out coins: {}", amount_payed);
+		Ok(new_supply)
+	}
 
-		// safe to do this late because of the test in the first line of the function
-		let new_supply = coin_supply + amount;
-		<CoinSupply>::put(new_supply);
-		native::info!("expanded supply by handing out coins: {}", amount);
-		Ok(())
+	/// Calculate the payouts of handing out `amount` Coins to `shares` without changing any state.
+	///
+	/// Returns the payout for each of `shares` (in the same order) and the new `HandoutCursor`
+	/// (see `hand_out_coins` for how Coins are distributed).
+	///
+	/// **Weight:**
+	/// - complexity: `O(S)` with `S` being `shares.len()` (the number of shareholders)
+	/// - DB access: 1 read for the handout cursor
+	fn handout_for(
+		shares: &[(T::AccountId, u64)],
+		amount: Coins,
+	) -> Result<(Vec<Coins>, u64), DispatchError> {
+		let share_supply = shares
+			.iter()
+			.try_fold(0u64, |supply, (_a, s)| supply.checked_add(*s))
+			.ok_or(Error::<T>::GenericOverflow)?;
+		ensure!(share_supply > 0, Error::<T>::NoShares);
+		let len = shares.len() as u64;
+		// Calculating in `u128` cannot overflow because both factors are at most `u64::MAX`.
+		// The result fits into `Coins` because `num_shares <= share_supply`.
+		let proportional_payouts: Vec<Coins> = shares
+			.iter()
+			.map(|(_acc, num_shares)| {
+				(u128::from(amount) * u128::from(*num_shares) / u128::from(share_supply)) as Coins
+			})
+			.collect();
+		// safe because the proportional payouts sum up to at most `amount`
+		let undistributed = amount - proportional_payouts.iter().sum::<Coins>();
+		// Every payout was rounded down by less than one coin, so `undistributed < len`
+		// and handing out 1 extra coin per shareholder pays out all of them.
+		let cursor = Self::handout_cursor() % len;
+		let payouts = proportional_payouts
+			.into_iter()
+			.enumerate()
+			.map(|(i, payout)| {
+				// position relative to the cursor, wrapping around at the end of the shareholders
+				let position = (i as u64 + len - cursor) % len;
+				let extra_payout = if position < undistributed { 1 } else { 0 };
+				payout + extra_payout
+			})
+			.collect();
+		Ok((payouts, (cursor + undistributed) % len))
+	}
+
+	/// Return what each shareholder would receive if `expansion` Coins were handed out to
+	/// the shareholders (i.e., the part of an expansion left after paying out bonds).
+	///
+	/// Mirrors the distribution of `hand_out_coins` including the extra Coins from rounding,
+	/// but does not change any state. Returns an empty list if
+	/// the Coins cannot be handed out (e.g., because there are no shares).
+	///
+	/// **Weight:**
+	/// - complexity: `O(S)` with `S` being the number of shareholders
+	/// - DB access: the DB accesses of `shares` + 1 read for the handout cursor
+	pub fn projected_shareholder_payout(expansion: Coins) -> Vec<(T::AccountId, Coins)> {
+		let shares = Self::shares();
+		match Self::handout_for(&shares, expansion) {
+			Ok((payouts, _cursor)) => {
+				shares.into_iter().map(|(acc, _num_shares)| acc).zip(payouts).collect()
+			}
+			Err(_) => Vec::new(),
+		}
 	}
 
 	// ------------------------------------------------------------
@@ -939,56 +3297,185 @@ impl<T: Trait> Module<T> {
 
 	/// Contracts or expands the supply based on conditions.
 	///
+	/// A `price` of `None` means the price feed is stale: the adjustment is skipped
+	/// and `AdjustmentSkippedStalePrice` is emitted instead.
+	/// Prices outside of `MinPriceBound` and `MaxPriceBound` are skipped as well,
+	/// emitting `PriceOutOfBounds`.
+	/// Emits `ReturnedToPeg` if the adjustment leaves the supply unchanged after the previous
+	/// one expanded or contracted it.
+	///
 	/// **Weight:**
-	/// Calls `expand_or_contract_on_price` every `AdjustmentFrequency` blocks.
+	/// Calls `expand_or_contract_on_price` every `adjustment_frequency()` blocks
+	/// after emitting the observed price.
 	/// - complexity: `O(P)` with `P` being the complexity of `expand_or_contract_on_price`
-	fn on_block_with_price(block: T::BlockNumber, price: Coins) -> DispatchResult {
+	fn on_block_with_price(block: T::BlockNumber, price: Option<Coins>) -> DispatchResult {
+		if let Some(price) = price {
+			<LastObservedPrice>::put(price);
+		}
 		// This can be changed to only correct for small or big price swings.
-		if block % T::AdjustmentFrequency::get() == 0.into() {
-			Self::expand_or_contract_on_price(price)
-		} else {
-			Ok(())
+		if block % Self::adjustment_frequency() != 0.into() {
+			return Ok(());
+		}
+		match price {
+			Some(price) if price < T::MinPriceBound::get() || price > T::MaxPriceBound::get() => {
+				native::warn!("price {} is out of bounds --> skipping supply adjustment", price);
+				Self::deposit_event(RawEvent::PriceOutOfBounds(block, price));
+				Ok(())
+			}
+			Some(price) => {
+				Self::deposit_event(RawEvent::PriceObserved(block, price));
+				let previous = Self::last_adjustment_action();
+				Self::expand_or_contract_on_price(price)?;
+				let was_off_peg = matches!(
+					previous,
+					Some(SupplyAction::Expand(_)) | Some(SupplyAction::Contract(_))
+				);
+				if was_off_peg && Self::last_adjustment_action() == Some(SupplyAction::None) {
+					Self::deposit_event(RawEvent::ReturnedToPeg(block));
+				}
+				Ok(())
+			}
+			None => {
+				native::warn!("price feed is stale --> skipping supply adjustment");
+				Self::deposit_event(RawEvent::AdjustmentSkippedStalePrice(block));
+				Ok(())
+			}
 		}
 	}
 
 	/// Expands (if the price is too high) or contracts (if the price is too low) the coin supply.
 	///
+	/// The change is scaled by `AdjustmentDamping` and `ExpansionFactor` or `ContractionFactor`
+	/// (see `simulate_adjustment`). Records the action in `LastAdjustmentAction` on success.
+	///
 	/// **Weight:**
 	/// - complexity: `O(S + C)`
 	///   - `S` being the complexity of executing either `expand_supply` or `contract_supply`
 	///   - `C` being a constant amount of storage reads for coin supply
 	/// - DB access:
 	///   - 1 read for coin_supply
+	///   - 1 write for the last adjustment action
 	///   - execute `expand_supply` OR execute `contract_supply` which have DB accesses
 	fn expand_or_contract_on_price(price: Coins) -> DispatchResult {
+		let supply = Self::coin_supply();
+		let action = Self::simulate_adjustment(price)?;
+		match action {
+			SupplyAction::Contract(contract_by) => {
+				let burned = Self::contract_supply(supply, contract_by)?;
+				if burned < contract_by {
+					native::warn!(
+						"could only contract supply by {} instead of {} because of insufficient bids",
+						burned,
+						contract_by
+					);
+				}
+			}
+			SupplyAction::Expand(expand_by) => {
+				Self::expand_supply(supply, expand_by)?;
+			}
+			SupplyAction::None => {
+				native::info!("coin price is within the tolerance around base as is desired --> nothing to do");
+			}
+		}
+		<LastAdjustmentAction>::put(action);
+		Ok(())
+	}
+
+	/// Calculate the supply adjustment `expand_or_contract_on_price` would perform for `price`
+	/// without changing any state.
+	///
+	/// Note: A contraction might end up smaller than returned if there are not enough bids.
+	/// An expansion is capped so that the coin supply does not overflow.
+	/// Returns `SupplyAction::None` instead of expanding or contracting by zero Coins.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read for coin_supply
+	pub fn simulate_adjustment(price: Coins) -> Result<SupplyAction, DispatchError> {
+		let supply = Self::coin_supply();
+		let damping = T::AdjustmentDamping::get();
+		let action = match Self::deviation_of(price)? {
+			PriceDeviation::Above(fraction) => {
+				let change = damping * Self::supply_change_for(fraction, supply)?;
+				SupplyAction::Contract(T::ContractionFactor::get() * change)
+			}
+			PriceDeviation::Below(fraction) => {
+				// a change too big for `Coins` is capped below anyway
+				let change = damping * Self::supply_change_for(fraction, supply).unwrap_or(Coins::max_value());
+				let expand_by = T::ExpansionFactor::get() * change;
+				// cap the expansion to what `CoinSupply` can accommodate
+				let headroom = Coins::max_value() - supply;
+				if expand_by > headroom {
+					native::warn!("capping supply expansion of {} to {}", expand_by, headroom);
+				}
+				SupplyAction::Expand(min(expand_by, headroom))
+			}
+			PriceDeviation::AtPeg => SupplyAction::None,
+		};
+		// e.g., a supply that cannot expand any further is left unchanged
+		Ok(match action {
+			SupplyAction::Expand(0) | SupplyAction::Contract(0) => SupplyAction::None,
+			action => action,
+		})
+	}
+
+	/// Return the last observed price together with its deviation from `target_unit()`.
+	///
+	/// Reads `LastObservedPrice` instead of fetching the price because fetching might change the
+	/// state of the price oracle (e.g., of `ClampedPrice`).
+	/// Uses the same arithmetic as the supply adjustment. Returns `NoPriceObserved` if no price
+	/// was observed yet and `ZeroPrice` if the price is zero.
+	///
+	/// **Weight:**
+	/// - complexity: `O(1)`
+	/// - DB access: 1 read of the last observed price + the DB accesses of `target_unit()`
+	pub fn price_deviation() -> Result<(Coins, PriceDeviation), DispatchError> {
+		let price = Self::last_observed_price().ok_or(Error::<T>::NoPriceObserved)?;
+		Ok((price, Self::deviation_of(price)?))
+	}
+
+	/// Calculate the deviation of `price` from `target_unit()`.
+	///
+	/// Prices within `PegTolerance` of the target unit are considered `AtPeg`.
+	fn deviation_of(price: Coins) -> Result<PriceDeviation, DispatchError> {
+		let target = Self::target_unit();
+		let tolerance = T::PegTolerance::get() * target;
 		match price {
 			0 => {
 				native::error!("coin price is zero!");
-				return Err(DispatchError::from(Error::<T>::ZeroPrice));
-			}
-			price if price > T::BaseUnit::get() => {
-				// safe from underflow because `price` is checked to be greater than `BaseUnit`
-				let supply = Self::coin_supply();
-				let contract_by = Self::calculate_supply_change(price, T::BaseUnit::get(), supply);
-				Self::contract_supply(supply, contract_by)?;
-			}
-			price if price < T::BaseUnit::get() => {
-				// safe from underflow because `price` is checked to be less than `BaseUnit`
-				let supply = Self::coin_supply();
-				let expand_by = Self::calculate_supply_change(T::BaseUnit::get(), price, supply);
-				Self::expand_supply(supply, expand_by)?;
+				Err(DispatchError::from(Error::<T>::ZeroPrice))
 			}
-			_ => {
-				native::info!("coin price is equal to base as is desired --> nothing to do");
+			price if price.saturating_sub(target) <= tolerance && target.saturating_sub(price) <= tolerance => {
+				Ok(PriceDeviation::AtPeg)
 			}
+			price if price > target => Ok(PriceDeviation::Above(Self::fraction_above_one(price, target))),
+			price if price < target => Ok(PriceDeviation::Below(Self::fraction_above_one(target, price))),
+			_ => Ok(PriceDeviation::AtPeg),
 		}
-		Ok(())
+	}
+
+	/// Calculate `numerator / denominator - 1`.
+	///
+	/// Expects `numerator >= denominator > 0`.
+	fn fraction_above_one(numerator: u64, denominator: u64) -> Fraction {
+		Fraction::from_num(numerator) / Fraction::from_num(denominator) - Fraction::from_num(1)
 	}
 
 	/// Calculate the amount of supply change from a fraction given as `numerator` and `denominator`.
-	fn calculate_supply_change(numerator: u64, denominator: u64, supply: u64) -> u64 {
-		type Fix = FixedU128<U64>;
-		let fraction = Fix::from_num(numerator) / Fix::from_num(denominator) - Fix::from_num(1);
-		fraction.saturating_mul_int(supply as u128).to_num::<u64>()
+	///
+	/// Returns `GenericOverflow` if the change does not fit into `Coins` (e.g., for extreme prices).
+	#[cfg(test)]
+	fn calculate_supply_change(numerator: u64, denominator: u64, supply: u64) -> Result<u64, DispatchError> {
+		Self::supply_change_for(Self::fraction_above_one(numerator, denominator), supply)
+	}
+
+	/// Calculate the amount of supply change as `fraction * supply`.
+	///
+	/// Returns `GenericOverflow` if the change does not fit into `Coins`.
+	fn supply_change_for(fraction: Fraction, supply: u64) -> Result<u64, DispatchError> {
+		fraction
+			.checked_mul_int(supply as u128)
+			.and_then(|change| change.checked_to_num::<u64>())
+			.ok_or(DispatchError::from(Error::<T>::GenericOverflow))
 	}
 }
@@ -0,0 +1,269 @@
+//! # Price Oracle Adapters
+//!
+//! This module provides adapters that wrap another implementation of `FetchPrice`
+//! to make the price fed into the stablecoin more robust.
+//!
+//! + `ClampedPrice` limits how much the price can change per fetch.
+//! + `MovingAveragePrice` averages the price over the last fetches.
+//! + `CompositePrice` combines several oracles into their weighted median.
+//!
+//! Usage Example:
+//! ```rust,ignore
+//! use pallet_stablecoin::oracle::ClampedPrice;
+//!
+//! parameter_types! {
+//!     pub const MaxPriceDelta: Perbill = Perbill::from_percent(10);
+//! }
+//!
+//! impl pallet_stablecoin::Trait for Runtime {
+//!     // --snip--
+//!     type CoinPrice = ClampedPrice<some_price_oracle::Module<Runtime>, MaxPriceDelta>;
+//! }
+//! ```
+//!
+//! Note: The adapters keep their state in the storage of this pallet, so there
+//! should only be one instance of each adapter per runtime.
+
+use adapters::BoundedDeque;
+use core::cmp::{max, min};
+use core::marker::PhantomData;
+use frame_support::{
+	storage::{StorageMap, StorageValue},
+	traits::Get,
+};
+use sp_runtime::Perbill;
+use sp_std::prelude::*;
+
+use crate::{Coins, FetchPrice, LastClampedPrice, PriceHistory, PriceHistoryRange, PriceIndex};
+
+/// Price oracle adapter that clamps the price returned by `Inner` to within
+/// `MaxDelta` of the last returned price.
+///
+/// The price may always change by at least 1 (unless `MaxDelta` is zero).
+/// A price of 0 is passed through unchanged because it signals an oracle error.
+pub struct ClampedPrice<Inner, MaxDelta>(PhantomData<(Inner, MaxDelta)>);
+
+impl<Inner, MaxDelta> FetchPrice<Coins> for ClampedPrice<Inner, MaxDelta>
+where
+	Inner: FetchPrice<Coins>,
+	MaxDelta: Get<Perbill>,
+{
+	/// Fetch the price from `Inner` and clamp it.
+	///
+	/// **Weight:**
+	/// - complexity: `O(F)` with `F` being the complexity of `Inner::fetch_price()`
+	/// - DB access: 1 read and 1 write of the last price
+	fn fetch_price() -> Coins {
+		Self::clamp(Inner::fetch_price())
+	}
+
+	/// Fetch the price from `Inner` and clamp it or return `None` if `Inner` is stale.
+	///
+	/// The last price is not updated if `Inner` is stale.
+	///
+	/// **Weight:**
+	/// - complexity: `O(F)` with `F` being the complexity of `Inner::fetch_price_checked()`
+	/// - DB access: 1 read and 1 write of the last price
+	fn fetch_price_checked() -> Option<Coins> {
+		Inner::fetch_price_checked().map(Self::clamp)
+	}
+}
+
+impl<Inner, MaxDelta> ClampedPrice<Inner, MaxDelta>
+where
+	MaxDelta: Get<Perbill>,
+{
+	/// Clamp `price` to within `MaxDelta` of the last price and store it as the last price.
+	fn clamp(price: Coins) -> Coins {
+		if price == 0 {
+			return price;
+		}
+		let clamped = match LastClampedPrice::get() {
+			Some(last) => {
+				let max_delta = MaxDelta::get();
+				// at least 1 so the price cannot get stuck when `last` is small
+				let delta = if max_delta == Perbill::zero() {
+					0
+				} else {
+					max(max_delta * last, 1)
+				};
+				min(max(price, last.saturating_sub(delta)), last.saturating_add(delta))
+			}
+			None => price,
+		};
+		LastClampedPrice::put(clamped);
+		clamped
+	}
+}
+
+/// Ringbuffer of the last prices fetched by `MovingAveragePrice`.
+type PriceHistoryQueue = BoundedDeque<Coins, PriceHistoryRange, PriceHistory, PriceIndex>;
+
+/// Price oracle adapter that returns the average of the last `Window` prices
+/// returned by `Inner` (including the current one).
+///
+/// A price of 0 is passed through unchanged and not recorded because it signals an oracle error.
+pub struct MovingAveragePrice<Inner, Window>(PhantomData<(Inner, Window)>);
+
+impl<Inner, Window> FetchPrice<Coins> for MovingAveragePrice<Inner, Window>
+where
+	Inner: FetchPrice<Coins>,
+	Window: Get<PriceIndex>,
+{
+	/// Fetch the price from `Inner`, record it and return the moving average.
+	///
+	/// **Weight:**
+	/// - complexity: `O(F + W)`
+	///   - `F` being the complexity of `Inner::fetch_price()`
+	///   - `W` being the size of the window
+	/// - DB access:
+	///   - 1 write of the new price + potentially 1 removal of the oldest price
+	///   - read `W` prices + read and write the history bounds
+	fn fetch_price() -> Coins {
+		Self::record(Inner::fetch_price())
+	}
+
+	/// Fetch the price from `Inner`, record it and return the moving average or return
+	/// `None` if `Inner` is stale.
+	///
+	/// Nothing is recorded if `Inner` is stale.
+	///
+	/// **Weight:**
+	/// - complexity: `O(F + W)`
+	///   - `F` being the complexity of `Inner::fetch_price_checked()`
+	///   - `W` being the size of the window
+	/// - DB access: see `fetch_price`
+	fn fetch_price_checked() -> Option<Coins> {
+		Inner::fetch_price_checked().map(Self::record)
+	}
+}
+
+impl<Inner, Window> MovingAveragePrice<Inner, Window>
+where
+	Window: Get<PriceIndex>,
+{
+	/// Record `price` in the price history and return the moving average.
+	fn record(price: Coins) -> Coins {
+		if price == 0 {
+			return price;
+		}
+		let window = max(1, Window::get());
+		let mut prices = PriceHistoryQueue::new();
+		prices.push_back(price);
+		while prices.len() > window {
+			prices.pop_front();
+		}
+		prices.commit();
+
+		let (start, length) = PriceHistoryRange::get();
+		let sum: u128 = (0..length)
+			// the history is a ringbuffer so the index might wrap around
+			.map(|offset| PriceHistory::get(start.wrapping_add(offset)) as u128)
+			.sum();
+		// `length` is at least 1 because we just pushed a price
+		(sum / length as u128) as Coins
+	}
+}
+
+/// A set of price oracles combined by `CompositePrice`.
+///
+/// Implemented for tuples of up to 5 `FetchPrice<Coins>` implementations.
+pub trait FetchPrices {
+	/// Fetch the prices of all oracles in order.
+	fn fetch_prices() -> Vec<Coins>;
+
+	/// Fetch the prices of all oracles in order with `None` for stale oracles.
+	fn fetch_prices_checked() -> Vec<Option<Coins>>;
+}
+
+macro_rules! impl_fetch_prices {
+	($($feed:ident),+) => {
+		impl<$($feed: FetchPrice<Coins>),+> FetchPrices for ($($feed,)+) {
+			fn fetch_prices() -> Vec<Coins> {
+				let mut prices = Vec::new();
+				$(prices.push($feed::fetch_price());)+
+				prices
+			}
+
+			fn fetch_prices_checked() -> Vec<Option<Coins>> {
+				let mut prices = Vec::new();
+				$(prices.push($feed::fetch_price_checked());)+
+				prices
+			}
+		}
+	};
+}
+
+impl_fetch_prices!(A);
+impl_fetch_prices!(A, B);
+impl_fetch_prices!(A, B, C);
+impl_fetch_prices!(A, B, C, D);
+impl_fetch_prices!(A, B, C, D, E);
+
+/// Price oracle adapter that returns the weighted median of the prices returned by `Feeds`.
+///
+/// The i-th weight of `Weights` applies to the i-th feed; feeds without a weight are ignored.
+/// Feeds returning a price of 0 signal an oracle error and are excluded from the median.
+/// Returns 0 if no feed returned a valid price.
+/// Stale feeds are excluded as well and the composite price is only stale if all feeds are.
+pub struct CompositePrice<Feeds, Weights>(PhantomData<(Feeds, Weights)>);
+
+impl<Feeds, Weights> FetchPrice<Coins> for CompositePrice<Feeds, Weights>
+where
+	Feeds: FetchPrices,
+	Weights: Get<Vec<u32>>,
+{
+	/// Fetch the prices from all `Feeds` and return their weighted median.
+	///
+	/// **Weight:**
+	/// - complexity: `O(F + N log N)`
+	///   - `F` being the combined complexity of the feeds' `fetch_price()`
+	///   - `N` being the number of feeds
+	/// - DB access: none besides the feeds'
+	fn fetch_price() -> Coins {
+		Self::weighted_median(Feeds::fetch_prices())
+	}
+
+	/// Fetch the prices from all `Feeds` and return the weighted median of the feeds that are
+	/// not stale or `None` if all of them are stale.
+	///
+	/// **Weight:**
+	/// - complexity: `O(F + N log N)`
+	///   - `F` being the combined complexity of the feeds' `fetch_price_checked()`
+	///   - `N` being the number of feeds
+	/// - DB access: none besides the feeds'
+	fn fetch_price_checked() -> Option<Coins> {
+		let prices = Feeds::fetch_prices_checked();
+		if prices.iter().all(Option::is_none) {
+			return None;
+		}
+		// stale feeds are excluded from the median like failing ones
+		Some(Self::weighted_median(prices.into_iter().map(|price| price.unwrap_or(0)).collect()))
+	}
+}
+
+impl<Feeds, Weights> CompositePrice<Feeds, Weights>
+where
+	Weights: Get<Vec<u32>>,
+{
+	/// Return the weighted median of `prices`, ignoring prices of 0 (see `CompositePrice`).
+	fn weighted_median(prices: Vec<Coins>) -> Coins {
+		let mut weighted: Vec<(Coins, u64)> = prices
+			.into_iter()
+			.zip(Weights::get())
+			.filter(|(price, weight)| *price > 0 && *weight > 0)
+			.map(|(price, weight)| (price, weight as u64))
+			.collect();
+		weighted.sort();
+		let total: u64 = weighted.iter().map(|(_, weight)| weight).sum();
+		let mut cumulative = 0;
+		for (price, weight) in weighted {
+			cumulative += weight;
+			// the lower weighted median is the first price covering at least half of the total weight
+			if 2 * cumulative >= total {
+				return price;
+			}
+		}
+		0
+	}
+}
@@ -3,24 +3,41 @@ use super::*;
 use itertools::Itertools;
 use log;
 use more_asserts::*;
+use num_rational::Ratio;
 use quickcheck::{QuickCheck, TestResult};
 use rand::{thread_rng, Rng};
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use frame_support::{assert_ok, impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types, weights::Weight,
+};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, CheckedMul, IdentityLookup},
 	Fixed64, Perbill,
 };
 use sp_std::iter;
+use core::marker::PhantomData;
+use std::cell::{Cell, RefCell};
 use system;
 
+use crate::oracle::{ClampedPrice, CompositePrice, MovingAveragePrice};
+
 impl_outer_origin! {
 	pub enum Origin for Test {}
 }
 
+mod stablecoin {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		stablecoin<T>,
+	}
+}
+
 const TEST_BASE_UNIT: u64 = 1000;
 static LAST_PRICE: AtomicU64 = AtomicU64::new(TEST_BASE_UNIT);
 pub struct RandomPrice;
@@ -39,6 +56,123 @@ impl FetchPrice<Coins> for RandomPrice {
 	}
 }
 
+/// Declare a test parameter implementing `Get` that returns `default` unless changed with `set`.
+///
+/// The value is stored per thread, so changing it in one test does not affect the others.
+macro_rules! test_param {
+	($(#[$attr:meta])* $name:ident: $type:ty = $default:expr) => {
+		$(#[$attr])*
+		pub struct $name;
+
+		impl $name {
+			fn value() -> &'static std::thread::LocalKey<Cell<$type>> {
+				thread_local! {
+					static VALUE: Cell<$type> = Cell::new($default);
+				}
+				&VALUE
+			}
+
+			fn set(value: $type) {
+				Self::value().with(|v| v.set(value));
+			}
+		}
+
+		impl Get<$type> for $name {
+			fn get() -> $type {
+				Self::value().with(|v| v.get())
+			}
+		}
+	};
+}
+
+test_param!(OraclePrice: Coins = TEST_BASE_UNIT);
+test_param!(OracleStale: bool = false);
+test_param!(BaseUnit: Coins = TEST_BASE_UNIT);
+test_param!(AdjustmentDamping: Perbill = Perbill::from_percent(100));
+test_param!(MaxBidsPerAccount: u64 = u64::max_value());
+test_param!(MaxBidActionsPerBlock: u32 = u32::max_value());
+test_param!(MinPriceBound: Coins = 0);
+test_param!(MaxPriceBound: Coins = u64::max_value());
+test_param!(MinimumBidQuantity: Coins = TEST_BASE_UNIT);
+test_param!(BondBonus: Perbill = Perbill::zero());
+test_param!(ExpansionFactor: Perbill = Perbill::from_percent(100));
+test_param!(ContractionFactor: Perbill = Perbill::from_percent(100));
+test_param!(PegTolerance: Perbill = Perbill::zero());
+test_param!(ExistentialDeposit: Coins = 0);
+test_param!(PayoutMode: BondPayoutMode = BondPayoutMode::Fifo);
+test_param!(MaxBondPayoutPerExpansion: Coins = Coins::max_value());
+test_param!(EvictionPolicy: BidEvictionPolicy = BidEvictionPolicy::EvictLowest);
+test_param!(ShrinkingExpiration: bool = false);
+
+/// Price oracle returning `OraclePrice`.
+///
+/// Reports a stale feed if `OracleStale` is set.
+pub struct TestPrice;
+
+impl FetchPrice<Coins> for TestPrice {
+	fn fetch_price() -> Coins {
+		OraclePrice::get()
+	}
+
+	fn fetch_price_checked() -> Option<Coins> {
+		if OracleStale::get() {
+			None
+		} else {
+			Some(Self::fetch_price())
+		}
+	}
+}
+
+/// Price oracle always returning the price `P`.
+pub struct FixedPrice<P>(PhantomData<P>);
+
+impl<P: Get<Coins>> FetchPrice<Coins> for FixedPrice<P> {
+	fn fetch_price() -> Coins {
+		P::get()
+	}
+}
+
+/// Bond expiration strategy using a fixed `ExpirationPeriod` unless `ShrinkingExpiration` is set,
+/// which shortens the period by one block per bond in the queue.
+pub struct TestExpiration;
+
+impl BondExpiration<BlockNumber> for TestExpiration {
+	fn expiration(now: BlockNumber, queue_length: BondIndex) -> BlockNumber {
+		if ShrinkingExpiration::get() {
+			now + ExpirationPeriod::get().saturating_sub(queue_length.into()).max(1)
+		} else {
+			FixedExpiration::<ExpirationPeriod>::expiration(now, queue_length)
+		}
+	}
+}
+
+/// A supply change reported to `RecordSupplyChanges`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SupplyChange {
+	Expand(Coins),
+	Contract(Coins),
+}
+
+thread_local! {
+	static SUPPLY_CHANGES: RefCell<Vec<SupplyChange>> = RefCell::new(Vec::new());
+}
+
+/// Supply change handler recording all callbacks, see `supply_changes`.
+pub struct RecordSupplyChanges;
+
+impl OnSupplyChange for RecordSupplyChanges {
+	fn on_expand(amount: Coins) {
+		SUPPLY_CHANGES.with(|c| c.borrow_mut().push(SupplyChange::Expand(amount)));
+	}
+	fn on_contract(amount: Coins) {
+		SUPPLY_CHANGES.with(|c| c.borrow_mut().push(SupplyChange::Contract(amount)));
+	}
+}
+
+fn supply_changes() -> Vec<SupplyChange> {
+	SUPPLY_CHANGES.with(|c| c.borrow().clone())
+}
+
 // For testing the pallet, we construct most of a mock runtime. This means
 // first constructing a configuration type (`Test`) which `impl`s each of the
 // configuration traits of modules we want to use.
@@ -53,14 +187,24 @@ parameter_types! {
 
 	// expire bonds quickly in tests
 	pub const ExpirationPeriod: u64 = 100;
+	// expire bids later than bonds
+	pub const BidExpirationPeriod: u64 = 1_000;
 	// allow few bids
 	pub const MaximumBids: u64 = 10;
+	pub const MaxTransfersPerBatch: u32 = 5;
+	pub const MaximumBonds: BondIndex = 500;
 	// adjust supply every second block
 	pub const AdjustmentFrequency: u64 = 2;
-	pub const BaseUnit: u64 = TEST_BASE_UNIT;
-	pub const InitialSupply: u64 = 100 * BaseUnit::get();
-	pub const MinimumSupply: u64 = BaseUnit::get();
+	pub const Symbol: &'static [u8] = b"TST";
+	pub const InitialSupply: u64 = 100 * TEST_BASE_UNIT;
+	pub const MinimumSupply: u64 = TEST_BASE_UNIT;
 	pub const MinimumBondPrice: Perbill = Perbill::from_percent(10);
+	pub const MaximumBondExpirationsPerBlock: u32 = 10;
+
+	pub const MaxPriceDelta: Perbill = Perbill::from_percent(10);
+	pub const PriceWindow: PriceIndex = 4;
+	pub const FeedPriceA: Coins = 1_000;
+	pub const FeedPriceB: Coins = 1_010;
 }
 
 type AccountId = u64;
@@ -76,7 +220,7 @@ impl system::Trait for Test {
 	type AccountId = AccountId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type MaximumBlockLength = MaximumBlockLength;
@@ -89,15 +233,37 @@ impl system::Trait for Test {
 }
 
 impl Trait for Test {
-	type Event = ();
-	type CoinPrice = RandomPrice;
+	type Event = TestEvent;
+	type CoinPrice = TestPrice;
 	type ExpirationPeriod = ExpirationPeriod;
+	type BidExpirationPeriod = BidExpirationPeriod;
+	type BondExpiration = TestExpiration;
+	type AdminOrigin = system::EnsureRoot<AccountId>;
 	type MaximumBids = MaximumBids;
+	type MaxTransfersPerBatch = MaxTransfersPerBatch;
+	type MaxBidsPerAccount = MaxBidsPerAccount;
+	type MaxBidActionsPerBlock = MaxBidActionsPerBlock;
+	type BidEvictionPolicy = EvictionPolicy;
+	type MaximumBonds = MaximumBonds;
+	type BondPayoutMode = PayoutMode;
+	type MaxBondPayoutPerExpansion = MaxBondPayoutPerExpansion;
 	type AdjustmentFrequency = AdjustmentFrequency;
+	type AdjustmentDamping = AdjustmentDamping;
+	type PegTolerance = PegTolerance;
+	type ExpansionFactor = ExpansionFactor;
+	type ContractionFactor = ContractionFactor;
 	type BaseUnit = BaseUnit;
+	type MinimumBidQuantity = MinimumBidQuantity;
+	type MinPriceBound = MinPriceBound;
+	type MaxPriceBound = MaxPriceBound;
+	type Symbol = Symbol;
 	type InitialSupply = InitialSupply;
 	type MinimumSupply = MinimumSupply;
 	type MinimumBondPrice = MinimumBondPrice;
+	type BondBonus = BondBonus;
+	type MaximumBondExpirationsPerBlock = MaximumBondExpirationsPerBlock;
+	type ExistentialDeposit = ExistentialDeposit;
+	type OnSupplyChange = RecordSupplyChanges;
 }
 
 type System = system::Module<Test>;
@@ -112,29 +278,59 @@ fn new_test_ext() -> sp_io::TestExternalities {
 		.zip(iter::repeat(1))
 		.collect();
 	// make sure to run our storage build function to check config
-	let _ = GenesisConfig::<Test> { shareholders }.assimilate_storage(&mut storage);
+	let _ = GenesisConfig::<Test> {
+		shareholders,
+		balances: vec![],
+	}
+	.assimilate_storage(&mut storage);
 	storage.into()
 }
 
 fn new_test_ext_with(shareholders: Vec<AccountId>) -> sp_io::TestExternalities {
+	new_test_ext_with_shares(shareholders.into_iter().zip(iter::repeat(1)).collect())
+}
+
+fn new_test_ext_with_shares(shareholders: Vec<(AccountId, u64)>) -> sp_io::TestExternalities {
 	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
-	let shareholders: Vec<(AccountId, u64)> = shareholders.into_iter().zip(iter::repeat(1)).collect();
 	// make sure to run our storage build function to check config
-	let _ = GenesisConfig::<Test> { shareholders }.assimilate_storage(&mut storage);
+	let _ = GenesisConfig::<Test> {
+		shareholders,
+		balances: vec![],
+	}
+	.assimilate_storage(&mut storage);
 	storage.into()
 }
 
 // ------------------------------------------------------------
 // utils
 type BondT = Bond<AccountId, BlockNumber>;
-// Implementation that we will instantiate.
-type Transient =
-	BoundedDeque<BondT, <Stablecoin as Store>::BondsRange, <Stablecoin as Store>::Bonds, BondIndex>;
+type BidT = Bid<AccountId, BlockNumber>;
+
+/// The originating bid price of bonds created directly in tests.
+fn test_bond_price() -> Perbill {
+	Perbill::from_percent(80)
+}
+
+/// Return the account, price and quantity of `bids`.
+fn bid_terms(bids: Vec<BidT>) -> Vec<(AccountId, Perbill, Coins)> {
+	bids.into_iter()
+		.map(|bid| (bid.account, bid.price, bid.quantity))
+		.collect()
+}
 
 fn add_bond(bond: BondT) {
-	let mut bonds = Transient::new();
-	bonds.push_back(bond);
-	bonds.commit();
+	assert_ok!(Stablecoin::push_bonds(vec![bond]));
+}
+
+// Note: Make sure to set the block number to something other than 0 if you want to check events.
+fn stablecoin_events() -> Vec<RawEvent<AccountId, BlockNumber>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			TestEvent::stablecoin(event) => Some(event),
+			_ => None,
+		})
+		.collect()
 }
 
 // ------------------------------------------------------------
@@ -160,220 +356,2365 @@ fn init_test() {
 		);
 		let share_supply: u64 = shares.iter().map(|(_a, s)| s).sum();
 		assert_eq!(share_supply, 10);
+		assert_eq!(Stablecoin::share_supply(), 10);
 	});
 }
 
-// ------------------------------------------------------------
-// balances
 #[test]
-fn transfer_test() {
+fn shares_of_test() {
 	new_test_ext().execute_with(|| {
-		let first_acc = 1;
-		let second_acc = 2;
-		let amount = TEST_BASE_UNIT;
-		let from_balance_before = Stablecoin::get_balance(first_acc);
-		let to_balance_before = Stablecoin::get_balance(second_acc);
-		assert_ok!(Stablecoin::transfer_from_to(&first_acc, &second_acc, amount));
-		assert_eq!(Stablecoin::get_balance(first_acc), from_balance_before - amount);
-		assert_eq!(Stablecoin::get_balance(second_acc), to_balance_before + amount);
+		for acc in 1..=10 {
+			assert_eq!(Stablecoin::shares_of(acc), 1);
+		}
+		assert_eq!(Stablecoin::shares_of(11), 0);
+
+		assert_ok!(Stablecoin::transfer_shares(Origin::signed(1), 11, 1));
+		assert_eq!(Stablecoin::shares_of(1), 0);
+		assert_eq!(Stablecoin::shares_of(11), 1);
 	});
 }
 
-// ------------------------------------------------------------
-// currency trait
 #[test]
-fn slash_test() {
-	new_test_ext().execute_with(|| {
-		let acc = 1;
-		let amount = TEST_BASE_UNIT;
-		let balance_before = Stablecoin::get_balance(acc);
-		assert_eq!(Stablecoin::slash(&acc, amount), 0);
-		assert_eq!(Stablecoin::get_balance(acc), balance_before - amount);
+#[should_panic(expected = "`BaseUnit` needs to be greater than zero")]
+fn init_rejects_zero_base_unit() {
+	BaseUnit::set(0);
+	new_test_ext();
+}
+
+#[test]
+fn init_with_weighted_shares() {
+	new_test_ext_with_shares(vec![(1, 1), (2, 3)]).execute_with(|| {
+		assert_eq!(Stablecoin::shares(), vec![(1, 1), (2, 3)]);
+		assert_eq!(Stablecoin::share_supply(), 4);
+		assert_eq!(Stablecoin::get_balance(1), InitialSupply::get() / 4);
+		assert_eq!(Stablecoin::get_balance(2), InitialSupply::get() * 3 / 4);
+		assert_eq!(Stablecoin::coin_supply(), InitialSupply::get());
 	});
 }
 
-// ------------------------------------------------------------
-// bids
 #[test]
-fn bids_are_sorted_highest_to_lowest() {
+#[should_panic(expected = "every shareholder needs at least one share")]
+fn init_rejects_zero_shares() {
+	new_test_ext_with_shares(vec![(1, 1), (2, 0)]);
+}
+
+#[test]
+#[should_panic(expected = "shareholders need to be unique")]
+fn init_rejects_duplicate_shareholders() {
+	new_test_ext_with(vec![1, 1, 2]);
+}
+
+#[test]
+fn init_with_balances() {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let balances = vec![(1, 60 * TEST_BASE_UNIT), (2, 40 * TEST_BASE_UNIT)];
+	GenesisConfig::<Test> {
+		shareholders: vec![(1, 3), (3, 1)],
+		balances,
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| {
+		assert_eq!(Stablecoin::coin_supply(), InitialSupply::get());
+		assert_eq!(Stablecoin::get_balance(1), 60 * TEST_BASE_UNIT);
+		assert_eq!(Stablecoin::get_balance(2), 40 * TEST_BASE_UNIT);
+		assert_eq!(Stablecoin::get_balance(3), 0);
+		// the shares are independent of the initial balances
+		assert_eq!(Stablecoin::shares(), vec![(1, 3), (3, 1)]);
+		assert_eq!(Stablecoin::share_supply(), 4);
+	});
+}
+
+#[test]
+#[should_panic(expected = "initial `balances` need to sum up to `InitialSupply`")]
+fn init_rejects_balances_not_matching_initial_supply() {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let _ = GenesisConfig::<Test> {
+		shareholders: vec![(1, 1)],
+		balances: vec![(1, TEST_BASE_UNIT)],
+	}
+	.assimilate_storage(&mut storage);
+}
+
+#[test]
+fn storage_version_is_set_at_genesis() {
 	new_test_ext().execute_with(|| {
-		let bid_amount = 5 * BaseUnit::get();
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(33), bid_amount));
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), bid_amount));
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+	});
+}
 
-		let bids = Stablecoin::bond_bids();
-		let prices: Vec<_> = bids.into_iter().map(|Bid { price, .. }| price).collect();
-		// largest bid is stored last so we can pop
-		assert_eq!(
-			prices,
-			vec![
-				Perbill::from_percent(25),
-				Perbill::from_percent(33),
-				Perbill::from_percent(50),
-			]
-		);
+#[test]
+fn migrate_unversioned_storage() {
+	new_test_ext().execute_with(|| {
+		<StorageVersion>::kill();
+		assert_eq!(Stablecoin::storage_version(), 0);
+		Stablecoin::migrate();
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		// migrating again is a no-op
+		Stablecoin::migrate();
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
 	});
 }
 
 #[test]
-fn amount_of_bids_is_limited() {
+fn migrate_unversioned_bonds_and_bids() {
 	new_test_ext().execute_with(|| {
-		let bid_amount = 5 * BaseUnit::get();
-		for _i in 0..(2 * MaximumBids::get()) {
-			Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(2, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(3, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(2, 2 * payout, test_bond_price()));
+		let (start, length) = Stablecoin::bonds_range();
+		for offset in 0..length {
+			let index = start.wrapping_add(offset);
+			let bond = Stablecoin::get_bond(index);
+			// bonds encoded like the version 0 `Bond` struct
+			unhashed::put(
+				&<Bonds<Test>>::hashed_key_for(index),
+				&(bond.account, bond.payout, bond.expiration),
+			);
 		}
+		// the bonds of 3 predate `BondsByAccount`, those of 2 were already indexed
+		<BondsByAccount<Test>>::remove(3);
+		// bids encoded like the version 0 `Bid` struct
+		let old_bids: Vec<(AccountId, Perbill, Coins)> = vec![
+			(1, Perbill::from_percent(50), payout),
+			(2, Perbill::from_percent(60), payout),
+		];
+		unhashed::put(&Stablecoin::storage_key(b"BondBids"), &old_bids);
+		<LockedInBids>::kill();
+		<StorageVersion>::kill();
 
-		assert_eq!(Stablecoin::bond_bids().len() as u64, MaximumBids::get());
+		Stablecoin::migrate();
+
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		assert_eq!(Stablecoin::bond_indices_of(2), vec![start, start.wrapping_add(2)]);
+		assert_eq!(Stablecoin::bond_indices_of(3), vec![start.wrapping_add(1)]);
+		assert_eq!(Stablecoin::bonds_of(&3)[0].1.account, 3);
+		assert_eq!(Stablecoin::total_locked_in_bids(), 500 + 600);
 	});
 }
 
 #[test]
-fn truncated_bids_are_refunded() {
-	new_test_ext_with(vec![1]).execute_with(|| {
-		let price = Perbill::from_percent(25);
-		let quantity = BaseUnit::get();
-		for _i in 0..(MaximumBids::get() + 1) {
-			assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+fn migrate_shares_vec_to_map() {
+	new_test_ext().execute_with(|| {
+		let old_shares: Vec<(AccountId, u64)> = vec![(3, 2), (1, 5), (2, 1)];
+		<Shareholders<Test>>::kill();
+		for acc in 1..=10 {
+			<Shares<Test>>::remove(acc);
 		}
+		<ShareSupply>::kill();
+		unhashed::put(&Stablecoin::shares_storage_prefix(), &old_shares);
+		<StorageVersion>::put(1);
 
-		assert_eq!(Stablecoin::bond_bids().len() as u64, MaximumBids::get());
-		let expected = InitialSupply::get() - price * quantity * (MaximumBids::get() as u64);
-		assert_eq!(Stablecoin::get_balance(1), expected);
+		Stablecoin::migrate();
+
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		assert_eq!(Stablecoin::shares(), old_shares, "order of shareholders should be kept");
+		assert_eq!(Stablecoin::share_supply(), 8);
+		assert_eq!(unhashed::get::<Vec<(AccountId, u64)>>(&Stablecoin::shares_storage_prefix()), None);
 	});
 }
 
 #[test]
-fn cancel_all_bids_test() {
+fn migrate_bonds_without_price() {
 	new_test_ext().execute_with(|| {
-		let bid_amount = 5 * BaseUnit::get();
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
-		Stablecoin::add_bid(Bid::new(2, Perbill::from_percent(33), bid_amount));
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), bid_amount));
-		Stablecoin::add_bid(Bid::new(3, Perbill::from_percent(50), bid_amount));
-		assert_eq!(Stablecoin::bond_bids().len(), 4);
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(2, payout, test_bond_price()));
+		let (start, _) = Stablecoin::bonds_range();
+		let expiration = Stablecoin::get_bond(start).expiration;
+		unhashed::put(&<Bonds<Test>>::hashed_key_for(start), &(2 as AccountId, payout, expiration));
+		<StorageVersion>::put(2);
 
-		assert_ok!(Stablecoin::cancel_all_bids(Origin::signed(1)));
+		Stablecoin::migrate();
 
-		let bids = Stablecoin::bond_bids();
-		assert_eq!(bids.len(), 2);
-		for bid in bids {
-			assert!(bid.account != 1);
-		}
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		let bond = Stablecoin::get_bond(start);
+		assert_eq!(bond.account, 2);
+		assert_eq!(bond.payout, payout);
+		assert_eq!(bond.expiration, expiration);
+		assert_eq!(bond.price, Perbill::zero());
 	});
 }
 
 #[test]
-fn cancel_selected_bids_test() {
+fn migrate_clears_bid_actions() {
 	new_test_ext().execute_with(|| {
-		let bid_amount = 5 * BaseUnit::get();
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
-		Stablecoin::add_bid(Bid::new(2, Perbill::from_percent(33), bid_amount));
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(45), bid_amount));
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), bid_amount));
-		Stablecoin::add_bid(Bid::new(3, Perbill::from_percent(55), bid_amount));
-		assert_eq!(Stablecoin::bond_bids().len(), 5);
+		// counts encoded like in version 6, keyed by account only
+		let mut key = Stablecoin::storage_key(b"BidActions");
+		key.extend_from_slice(&sp_io::hashing::blake2_128(&(1 as AccountId).encode()));
+		key.extend_from_slice(&(1 as AccountId).encode());
+		unhashed::put(&key, &(3 as BlockNumber, 2u32));
+		<StorageVersion>::put(6);
 
-		assert_ok!(Stablecoin::cancel_bids_at_or_below(
-			Origin::signed(1),
-			Perbill::from_percent(45)
-		));
+		Stablecoin::migrate();
 
-		let bids = Stablecoin::bond_bids();
-		assert_eq!(bids.len(), 3);
-		let bids: Vec<(_, _)> = bids
-			.into_iter()
-			.map(|Bid { account, price, .. }| (account, price))
-			.collect();
-		// highest bid is last so we can pop
-		assert_eq!(
-			bids,
-			vec![
-				(2, Perbill::from_percent(33)),
-				(1, Perbill::from_percent(50)),
-				(3, Perbill::from_percent(55)),
-			]
-		);
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		assert_eq!(unhashed::get::<(BlockNumber, u32)>(&key), None);
 	});
 }
 
 // ------------------------------------------------------------
-// bonds
+// shares
 #[test]
-fn adding_bonds() {
+fn transfer_shares_test() {
 	new_test_ext().execute_with(|| {
-		let payout = Fixed64::from_rational(20, 100).saturated_multiply_accumulate(BaseUnit::get());
-		add_bond(Stablecoin::new_bond(3, payout));
+		assert_ok!(Stablecoin::transfer_shares(Origin::signed(1), 2, 1));
+		let shares = Stablecoin::shares();
+		assert!(shares.iter().all(|(acc, _)| *acc != 1), "empty shareholder should be removed");
+		assert!(shares.contains(&(2, 2)));
 
-		let (start, length) = Stablecoin::bonds_range();
-		// computing the length this way is fine because there was no overflow
-		assert_eq!(length, 1);
-		let bond = &Stablecoin::get_bond(start);
-		assert_eq!(bond.expiration, System::block_number() + ExpirationPeriod::get());
-	})
+		assert_ok!(Stablecoin::transfer_shares(Origin::signed(2), 11, 1));
+		let shares = Stablecoin::shares();
+		assert!(shares.contains(&(2, 1)));
+		assert_eq!(shares.last(), Some(&(11, 1)), "new shareholders should be appended");
+
+		let share_supply: u64 = shares.iter().map(|(_a, s)| s).sum();
+		assert_eq!(share_supply, 10, "total shares should be unchanged");
+		assert_eq!(Stablecoin::share_supply(), 10);
+		assert!(!<Shares<Test>>::contains_key(1));
+	});
 }
 
 #[test]
-fn expire_bonds() {
-	new_test_ext_with(vec![1]).execute_with(|| {
-		let acc = 3;
-		let prev_acc_balance = Stablecoin::get_balance(acc);
-		let payout = Fixed64::from_rational(20, 100).saturated_multiply_accumulate(BaseUnit::get());
-		add_bond(Stablecoin::new_bond(acc, payout));
+fn transfer_shares_edge_cases() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Stablecoin::transfer_shares(Origin::signed(3), 4, 2),
+			Error::<Test>::InsufficientShares
+		);
+		assert_noop!(
+			Stablecoin::transfer_shares(Origin::signed(42), 4, 1),
+			Error::<Test>::InsufficientShares
+		);
 
-		let (start, length) = Stablecoin::bonds_range();
-		// computing the length this way is fine because there was no overflow
-		assert_eq!(length, 1);
-		let bond = &Stablecoin::get_bond(start);
-		assert_eq!(bond.expiration, System::block_number() + ExpirationPeriod::get());
+		let shares_before = Stablecoin::shares();
+		assert_ok!(Stablecoin::transfer_shares(Origin::signed(3), 3, 1));
+		assert_eq!(Stablecoin::shares(), shares_before);
+	});
+}
 
-		let prev_supply = Stablecoin::coin_supply();
-		// set blocknumber past expiration time
-		System::set_block_number(System::block_number() + ExpirationPeriod::get());
-		assert_ok!(Stablecoin::expand_supply(prev_supply, 42));
-		let acc_balance = Stablecoin::get_balance(acc);
+// ------------------------------------------------------------
+// balances
+#[test]
+fn transfer_test() {
+	new_test_ext().execute_with(|| {
+		let first_acc = 1;
+		let second_acc = 2;
+		let amount = TEST_BASE_UNIT;
+		let from_balance_before = Stablecoin::get_balance(first_acc);
+		let to_balance_before = Stablecoin::get_balance(second_acc);
+		assert_ok!(Stablecoin::transfer_from_to(&first_acc, &second_acc, amount));
+		assert_eq!(Stablecoin::get_balance(first_acc), from_balance_before - amount);
+		assert_eq!(Stablecoin::get_balance(second_acc), to_balance_before + amount);
+	});
+}
+
+#[test]
+fn transfer_event_contains_balances() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let amount = TEST_BASE_UNIT;
+		let from_balance_before = Stablecoin::get_balance(1);
+		let to_balance_before = Stablecoin::get_balance(2);
+		assert_ok!(Stablecoin::send_coins(Origin::signed(1), 2, amount));
 		assert_eq!(
-			prev_acc_balance, acc_balance,
-			"account balance should not change as the bond expired"
+			stablecoin_events(),
+			vec![RawEvent::Transfer(
+				1,
+				2,
+				amount,
+				from_balance_before - amount,
+				to_balance_before + amount
+			)]
 		);
 		assert_eq!(
-			prev_supply + 42,
-			Stablecoin::coin_supply(),
-			"coin supply should have increased"
+			Stablecoin::do_transfer(&2, &1, amount),
+			Ok((to_balance_before, from_balance_before))
 		);
 	});
 }
 
 #[test]
-fn expire_bonds_and_expand_supply() {
-	new_test_ext_with(vec![1]).execute_with(|| {
-		let first_acc = 3;
-		let prev_first_acc_balance = Stablecoin::get_balance(first_acc);
-		// 1.2 * BaseUnit
-		let payout = Fixed64::from_rational(20, 100).saturated_multiply_accumulate(BaseUnit::get());
-		add_bond(Stablecoin::new_bond(first_acc, payout));
+fn transfer_to_self_changes_nothing() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let amount = TEST_BASE_UNIT;
+		let balance = Stablecoin::get_balance(1);
+		let supply = Stablecoin::coin_supply();
 
-		let (start, length) = Stablecoin::bonds_range();
-		// computing the length this way is fine because there was no overflow
-		assert_eq!(length, 1);
-		let bond = &Stablecoin::get_bond(start);
-		assert_eq!(bond.expiration, System::block_number() + ExpirationPeriod::get());
+		assert_ok!(Stablecoin::send_coins(Origin::signed(1), 1, amount));
+		assert_eq!(
+			stablecoin_events(),
+			vec![RawEvent::Transfer(1, 1, amount, balance, balance)]
+		);
+		assert_eq!(Stablecoin::do_transfer(&1, &1, amount), Ok((balance, balance)));
+		assert_ok!(<Stablecoin as BasicCurrency<AccountId>>::transfer(&1, &1, amount));
 
-		let prev_supply = Stablecoin::coin_supply();
-		let second_acc = first_acc + 1;
-		let prev_second_acc_balance = Stablecoin::get_balance(second_acc);
+		assert_eq!(Stablecoin::get_balance(1), balance);
+		assert_eq!(Stablecoin::coin_supply(), supply);
+		assert_noop!(
+			Stablecoin::send_coins(Origin::signed(1), 1, balance + 1),
+			Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn zero_transfer_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Stablecoin::send_coins(Origin::signed(1), 2, 0),
+			Error::<Test>::ZeroAmount
+		);
+	});
+}
+
+#[test]
+fn transfer_errors_are_typed() {
+	new_test_ext().execute_with(|| {
+		let balance = Stablecoin::get_balance(1);
+		assert_noop!(
+			Stablecoin::send_coins(Origin::signed(1), 2, balance + 1),
+			Error::<Test>::InsufficientBalance
+		);
+		<Balance<Test>>::insert(3, u64::max_value());
+		assert_noop!(
+			Stablecoin::transfer_from_to(&1, &3, 1),
+			Error::<Test>::BalanceOverflow
+		);
+	});
+}
+
+#[test]
+fn transfer_removes_empty_balance() {
+	new_test_ext().execute_with(|| {
+		let balance = Stablecoin::get_balance(1);
+		assert_ok!(Stablecoin::transfer_from_to(&1, &2, balance));
+		assert_eq!(Stablecoin::get_balance(1), 0);
+		assert!(!<Balance<Test>>::contains_key(1));
+		assert_eq!(Stablecoin::get_balance(2), 2 * balance);
+	});
+}
+
+#[test]
+fn transfer_respects_existential_deposit() {
+	ExistentialDeposit::set(100);
+	new_test_ext().execute_with(|| {
+		let balance = Stablecoin::get_balance(1);
+		assert_noop!(
+			Stablecoin::transfer_from_to(&1, &2, balance - 50),
+			Error::<Test>::BelowExistentialDeposit
+		);
+		assert_ok!(Stablecoin::transfer_from_to(&1, &2, balance - 100));
+		assert_eq!(Stablecoin::get_balance(1), 100);
+		// sending everything is always allowed
+		assert_ok!(Stablecoin::transfer_from_to(&1, &2, 100));
+		assert!(!<Balance<Test>>::contains_key(1));
+	});
+}
+
+#[test]
+fn burn_test() {
+	new_test_ext().execute_with(|| {
+		let acc = 1;
+		let amount = TEST_BASE_UNIT;
+		let balance_before = Stablecoin::get_balance(acc);
+		let supply_before = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::burn(Origin::signed(acc), amount));
+		assert_eq!(Stablecoin::get_balance(acc), balance_before - amount);
+		assert_eq!(Stablecoin::coin_supply(), supply_before - amount);
+	});
+}
+
+#[test]
+fn burn_respects_minimum_supply() {
+	new_test_ext_with(vec![1]).execute_with(|| {
+		let amount = InitialSupply::get() - MinimumSupply::get() + 1;
+		assert_noop!(
+			Stablecoin::burn(Origin::signed(1), amount),
+			Error::<Test>::CoinSupplyUnderflow
+		);
+		assert_ok!(Stablecoin::burn(Origin::signed(1), amount - 1));
+		assert_eq!(Stablecoin::coin_supply(), MinimumSupply::get());
+	});
+}
+
+// ------------------------------------------------------------
+// currency trait
+#[test]
+fn slash_test() {
+	new_test_ext().execute_with(|| {
+		let acc = 1;
+		let amount = TEST_BASE_UNIT;
+		let balance_before = Stablecoin::get_balance(acc);
+		assert_eq!(Stablecoin::slash(&acc, amount), 0);
+		assert_eq!(Stablecoin::get_balance(acc), balance_before - amount);
+	});
+}
+
+#[test]
+fn currency_respects_minimum_supply() {
+	use frame_support::traits::{Currency, ExistenceRequirement, WithdrawReasons};
+
+	new_test_ext_with(vec![1]).execute_with(|| {
+		let amount = InitialSupply::get() - MinimumSupply::get() + 1;
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::withdraw(
+				&1,
+				amount,
+				WithdrawReasons::all(),
+				ExistenceRequirement::AllowDeath
+			)
+			.map(|_| ()),
+			Error::<Test>::CoinSupplyUnderflow
+		);
+		let imbalance = <Stablecoin as Currency<AccountId>>::withdraw(
+			&1,
+			amount - 1,
+			WithdrawReasons::all(),
+			ExistenceRequirement::AllowDeath,
+		);
+		assert!(imbalance.is_ok());
+		drop(imbalance);
+		assert_eq!(Stablecoin::coin_supply(), MinimumSupply::get());
+
+		// burning saturates at the minimum supply
+		let burned = <Stablecoin as Currency<AccountId>>::burn(1);
+		assert_eq!(Stablecoin::coin_supply(), MinimumSupply::get());
+		drop(burned);
+		assert_eq!(Stablecoin::coin_supply(), MinimumSupply::get());
+
+		// slashing is exempt
+		let (slashed, _) = <Stablecoin as Currency<AccountId>>::slash(&1, 1);
+		drop(slashed);
+		assert_eq!(Stablecoin::coin_supply(), MinimumSupply::get() - 1);
+	});
+}
+
+#[test]
+fn paused_pallet_blocks_currency_operations() {
+	use frame_support::traits::{Currency, ExistenceRequirement, WithdrawReasons};
+
+	new_test_ext().execute_with(|| {
+		let amount = TEST_BASE_UNIT;
+		let supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::set_paused(system::RawOrigin::Root.into(), true));
+
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::transfer(&1, &2, amount, ExistenceRequirement::AllowDeath),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::withdraw(
+				&1,
+				amount,
+				WithdrawReasons::all(),
+				ExistenceRequirement::AllowDeath
+			)
+			.map(|_| ()),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::deposit_into_existing(&1, amount).map(|_| ()),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::ensure_can_withdraw(
+				&1,
+				amount,
+				WithdrawReasons::all(),
+				0
+			),
+			Error::<Test>::Paused
+		);
+		let _ = <Stablecoin as Currency<AccountId>>::deposit_creating(&42, amount);
+		assert_eq!(Stablecoin::get_balance(42), 0);
+		assert_eq!(Stablecoin::coin_supply(), supply);
+	});
+}
+
+#[test]
+fn frozen_account_blocks_currency_operations() {
+	use frame_support::traits::{Currency, ExistenceRequirement, WithdrawReasons};
+
+	new_test_ext().execute_with(|| {
+		let amount = TEST_BASE_UNIT;
+		let balance = Stablecoin::get_balance(1);
+		let supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::freeze_account(system::RawOrigin::Root.into(), 1));
+
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::withdraw(
+				&1,
+				amount,
+				WithdrawReasons::all(),
+				ExistenceRequirement::AllowDeath
+			)
+			.map(|_| ()),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::deposit_into_existing(&1, amount).map(|_| ()),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::ensure_can_withdraw(
+				&1,
+				amount,
+				WithdrawReasons::all(),
+				balance - amount
+			),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(Stablecoin::burn(Origin::signed(1), amount), Error::<Test>::AccountFrozen);
+		let _ = <Stablecoin as Currency<AccountId>>::deposit_creating(&1, amount);
+		assert_eq!(Stablecoin::get_balance(1), balance);
+		assert_eq!(Stablecoin::coin_supply(), supply);
+
+		// slashing still applies to frozen accounts
+		let (slashed, _) = <Stablecoin as Currency<AccountId>>::slash(&1, amount);
+		drop(slashed);
+		assert_eq!(Stablecoin::get_balance(1), balance - amount);
+	});
+}
+
+#[test]
+fn currency_deposit_and_withdraw_update_supply() {
+	use frame_support::traits::{Currency, ExistenceRequirement, WithdrawReasons};
+
+	new_test_ext().execute_with(|| {
+		let amount = TEST_BASE_UNIT;
+		let supply = Stablecoin::coin_supply();
+		assert_eq!(<Stablecoin as Currency<AccountId>>::total_issuance(), supply);
+		assert_eq!(<Stablecoin as Currency<AccountId>>::free_balance(&1), Stablecoin::get_balance(1));
+
+		// dropping the imbalance updates the supply
+		let _ = <Stablecoin as Currency<AccountId>>::deposit_creating(&42, 3 * amount);
+		assert_eq!(Stablecoin::get_balance(42), 3 * amount);
+		assert_eq!(Stablecoin::coin_supply(), supply + 3 * amount);
+
+		let withdrawn = <Stablecoin as Currency<AccountId>>::withdraw(
+			&42,
+			amount,
+			WithdrawReasons::all(),
+			ExistenceRequirement::AllowDeath,
+		);
+		assert!(withdrawn.is_ok());
+		drop(withdrawn);
+		assert_eq!(Stablecoin::get_balance(42), 2 * amount);
+		assert_eq!(Stablecoin::coin_supply(), supply + 2 * amount);
+
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::withdraw(
+				&42,
+				3 * amount,
+				WithdrawReasons::all(),
+				ExistenceRequirement::AllowDeath
+			)
+			.map(|_| ()),
+			Error::<Test>::InsufficientBalance
+		);
+		assert_eq!(
+			<Stablecoin as Currency<AccountId>>::deposit_into_existing(&43, amount).map(|_| ()),
+			Err(DispatchError::from(Error::<Test>::DeadAccount))
+		);
+		assert_eq!(Stablecoin::coin_supply(), supply + 2 * amount);
+	});
+}
+
+#[test]
+fn currency_offsetting_imbalances_keeps_supply() {
+	use frame_support::traits::{Currency, ExistenceRequirement, WithdrawReasons};
+
+	new_test_ext().execute_with(|| {
+		let amount = TEST_BASE_UNIT;
+		let supply = Stablecoin::coin_supply();
+		let balance = Stablecoin::get_balance(1);
+
+		let withdrawn = <Stablecoin as Currency<AccountId>>::withdraw(
+			&1,
+			amount,
+			WithdrawReasons::all(),
+			ExistenceRequirement::AllowDeath,
+		)
+		.expect("account 1 has enough balance");
+		<Stablecoin as Currency<AccountId>>::resolve_creating(&42, withdrawn);
+
+		assert_eq!(Stablecoin::get_balance(1), balance - amount);
+		assert_eq!(Stablecoin::get_balance(42), amount);
+		assert_eq!(Stablecoin::coin_supply(), supply, "moving coins should not change the supply");
+
+		assert_ok!(<Stablecoin as Currency<AccountId>>::transfer(
+			&42,
+			&43,
+			amount / 2,
+			ExistenceRequirement::KeepAlive
+		));
+		assert_eq!(Stablecoin::get_balance(43), amount / 2);
+
+		let (slashed, unslashed) = <Stablecoin as Currency<AccountId>>::slash(&43, amount);
+		assert_eq!(unslashed, amount - amount / 2);
+		drop(slashed);
+		assert_eq!(Stablecoin::coin_supply(), supply - amount / 2);
+	});
+}
+
+#[test]
+fn currency_transfer_keep_alive() {
+	use frame_support::traits::{Currency, ExistenceRequirement};
+
+	ExistentialDeposit::set(10);
+	new_test_ext().execute_with(|| {
+		let balance = Stablecoin::get_balance(1);
+		assert_noop!(
+			<Stablecoin as Currency<AccountId>>::transfer(&1, &2, balance, ExistenceRequirement::KeepAlive),
+			Error::<Test>::BelowExistentialDeposit
+		);
+		assert_ok!(<Stablecoin as Currency<AccountId>>::transfer(
+			&1,
+			&2,
+			balance,
+			ExistenceRequirement::AllowDeath
+		));
+		assert_eq!(Stablecoin::get_balance(1), 0);
+	});
+}
+
+// ------------------------------------------------------------
+// stablecoin interface
+fn query_through_interface<I: StablecoinInterface<AccountId>>(who: &AccountId) -> (Coins, Coins, Coins) {
+	(I::total_supply(), I::balance_of(who), I::minimum_supply())
+}
+
+#[test]
+fn interface_matches_storage_getters() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Stablecoin::transfer_from_to(&1, &2, TEST_BASE_UNIT));
+		let (total_supply, balance, minimum_supply) = query_through_interface::<Stablecoin>(&2);
+		assert_eq!(total_supply, Stablecoin::coin_supply());
+		assert_eq!(balance, Stablecoin::get_balance(2));
+		assert_eq!(minimum_supply, MinimumSupply::get());
+	});
+}
+
+#[test]
+fn decimals_are_derived_from_base_unit() {
+	// no storage access needed (and a big base unit would not pass the genesis checks)
+	assert_eq!(Stablecoin::decimals(), 3);
+	BaseUnit::set(1_000_000);
+	assert_eq!(Stablecoin::decimals(), 6);
+	BaseUnit::set(1_500_000);
+	assert_eq!(Stablecoin::decimals(), 6);
+	BaseUnit::set(1);
+	assert_eq!(Stablecoin::decimals(), 0);
+}
+
+#[test]
+fn governance_calls_require_admin_origin() {
+	new_test_ext().execute_with(|| {
+		let signed = || Origin::signed(1);
+		assert_noop!(
+			Stablecoin::set_minimum_bond_price(signed(), Perbill::from_percent(50)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(Stablecoin::set_adjustment_frequency(signed(), 5), DispatchError::BadOrigin);
+		assert_noop!(Stablecoin::set_target_unit(signed(), 2 * TEST_BASE_UNIT), DispatchError::BadOrigin);
+		assert_noop!(Stablecoin::thaw_account(signed(), 2), DispatchError::BadOrigin);
+		assert_noop!(Stablecoin::mint_shares(signed(), 1, 10), DispatchError::BadOrigin);
+		assert_noop!(Stablecoin::set_paused(signed(), true), DispatchError::BadOrigin);
+		assert_noop!(Stablecoin::force_expand(signed(), TEST_BASE_UNIT), DispatchError::BadOrigin);
+		assert_noop!(Stablecoin::force_contract(signed(), TEST_BASE_UNIT), DispatchError::BadOrigin);
+
+		assert_ok!(Stablecoin::set_adjustment_frequency(system::RawOrigin::Root.into(), 5));
+		assert_eq!(Stablecoin::adjustment_frequency(), 5);
+	});
+}
+
+#[test]
+fn frozen_accounts_cannot_transfer() {
+	new_test_ext().execute_with(|| {
+		let amount = TEST_BASE_UNIT;
+		assert_noop!(
+			Stablecoin::freeze_account(Origin::signed(1), 2),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Stablecoin::freeze_account(system::RawOrigin::Root.into(), 2));
+		assert!(Stablecoin::is_frozen(2));
+
+		assert_noop!(
+			Stablecoin::send_coins(Origin::signed(2), 1, amount),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			Stablecoin::send_coins(Origin::signed(1), 2, amount),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(2), Perbill::from_percent(50), amount),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bonds(Origin::signed(2), vec![(Perbill::from_percent(50), amount)]),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			Stablecoin::transfer_shares(Origin::signed(2), 1, 1),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			Stablecoin::transfer_shares(Origin::signed(1), 2, 1),
+			Error::<Test>::AccountFrozen
+		);
+
+		// frozen accounts still receive their share of expansions
+		let balance = Stablecoin::get_balance(2);
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 10 * amount));
+		assert_eq!(Stablecoin::get_balance(2), balance + amount);
+
+		assert_ok!(Stablecoin::thaw_account(system::RawOrigin::Root.into(), 2));
+		assert!(!Stablecoin::is_frozen(2));
+		assert_ok!(Stablecoin::send_coins(Origin::signed(2), 1, amount));
+	});
+}
+
+#[test]
+fn transfer_batch_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let balance = InitialSupply::get() / 10;
+		let amount = TEST_BASE_UNIT;
+		assert_ok!(Stablecoin::transfer_batch(
+			Origin::signed(1),
+			vec![(2, amount), (11, 2 * amount), (12, 3 * amount)]
+		));
+
+		assert_eq!(Stablecoin::get_balance(1), balance - 6 * amount);
+		assert_eq!(Stablecoin::get_balance(2), balance + amount);
+		assert_eq!(Stablecoin::get_balance(11), 2 * amount);
+		assert_eq!(Stablecoin::get_balance(12), 3 * amount);
+		assert_eq!(
+			stablecoin_events(),
+			vec![
+				RawEvent::Transfer(1, 2, amount, balance - amount, balance + amount),
+				RawEvent::Transfer(1, 11, 2 * amount, balance - 3 * amount, 2 * amount),
+				RawEvent::Transfer(1, 12, 3 * amount, balance - 6 * amount, 3 * amount),
+			]
+		);
+	});
+}
+
+#[test]
+fn transfer_batch_to_sender_keeps_its_balance() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let balance = InitialSupply::get() / 10;
+		let amount = TEST_BASE_UNIT;
+		assert_ok!(Stablecoin::transfer_batch(
+			Origin::signed(1),
+			vec![(2, amount), (1, amount), (3, amount)]
+		));
+
+		assert_eq!(Stablecoin::get_balance(1), balance - 2 * amount);
+		assert_eq!(
+			stablecoin_events(),
+			vec![
+				RawEvent::Transfer(1, 2, amount, balance - amount, balance + amount),
+				RawEvent::Transfer(1, 1, amount, balance - amount, balance - amount),
+				RawEvent::Transfer(1, 3, amount, balance - 2 * amount, balance + amount),
+			]
+		);
+	});
+}
+
+#[test]
+fn transfer_batch_to_sender_checks_the_remaining_balance() {
+	ExistentialDeposit::set(10);
+	new_test_ext().execute_with(|| {
+		let balance = Stablecoin::get_balance(1);
+		// the transfer to the sender does not move coins, so only 5 would be left
+		assert_noop!(
+			Stablecoin::transfer_batch(Origin::signed(1), vec![(2, balance - 5), (1, 5)]),
+			Error::<Test>::BelowExistentialDeposit
+		);
+		// the sender cannot transfer more to itself than it owns
+		assert_noop!(
+			Stablecoin::transfer_batch(Origin::signed(1), vec![(2, 5), (1, balance + 1)]),
+			Error::<Test>::InsufficientBalance
+		);
+		assert_ok!(Stablecoin::transfer_batch(
+			Origin::signed(1),
+			vec![(2, balance - 10), (1, balance)]
+		));
+		assert_eq!(Stablecoin::get_balance(1), 10);
+	});
+}
+
+#[test]
+fn transfer_batch_fails_without_transferring_anything() {
+	new_test_ext().execute_with(|| {
+		let balance = InitialSupply::get() / 10;
+		let amount = TEST_BASE_UNIT;
+		// the first transfers are covered, but not the last one
+		assert_noop!(
+			Stablecoin::transfer_batch(Origin::signed(1), vec![(2, amount), (3, amount), (4, balance)]),
+			Error::<Test>::InsufficientBalance
+		);
+		assert_noop!(
+			Stablecoin::transfer_batch(Origin::signed(1), vec![(2, amount), (3, 0)]),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			Stablecoin::transfer_batch(Origin::signed(1), vec![(2, Coins::max_value()), (3, amount)]),
+			Error::<Test>::BalanceOverflow
+		);
+		let too_many = (2..=7).map(|acc| (acc, amount)).collect();
+		assert_noop!(
+			Stablecoin::transfer_batch(Origin::signed(1), too_many),
+			Error::<Test>::BatchTooLarge
+		);
+		assert_eq!(Stablecoin::get_balance(1), balance);
+		assert_eq!(Stablecoin::get_balance(2), balance);
+	});
+}
+
+#[test]
+fn paused_pallet_blocks_transfers_and_bids() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let amount = TEST_BASE_UNIT;
+		assert_noop!(
+			Stablecoin::set_paused(Origin::signed(1), true),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Stablecoin::set_paused(system::RawOrigin::Root.into(), true));
+		assert!(Stablecoin::paused());
+
+		assert_noop!(
+			Stablecoin::send_coins(Origin::signed(1), 2, amount),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			<Stablecoin as BasicCurrency<AccountId>>::transfer(&1, &2, amount),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			Stablecoin::transfer_shares(Origin::signed(1), 2, 1),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(50), amount),
+			Error::<Test>::Paused
+		);
+		assert_noop!(Stablecoin::cancel_all_bids(Origin::signed(1)), Error::<Test>::Paused);
+		assert_noop!(
+			Stablecoin::force_expand(system::RawOrigin::Root.into(), amount),
+			Error::<Test>::Paused
+		);
+
+		assert_ok!(Stablecoin::set_paused(system::RawOrigin::Root.into(), false));
+		assert!(!Stablecoin::paused());
+		assert_ok!(Stablecoin::send_coins(Origin::signed(1), 2, amount));
+		assert_ok!(Stablecoin::bid_for_bond(
+			Origin::signed(1),
+			Perbill::from_percent(50),
+			amount
+		));
+		assert_eq!(
+			stablecoin_events(),
+			vec![
+				RawEvent::Paused,
+				RawEvent::Unpaused,
+				RawEvent::Transfer(
+					1,
+					2,
+					amount,
+					InitialSupply::get() / 10 - amount,
+					InitialSupply::get() / 10 + amount
+				),
+				RawEvent::NewBid(1, Perbill::from_percent(50), amount),
+			]
+		);
+	});
+}
+
+// ------------------------------------------------------------
+// bids
+#[test]
+fn remove_coins_rounds_in_favor_of_the_system() {
+	// 1 / 30% = 3.33.. --> 10 coins buy 33.33.. payout
+	let mut bid = BidT::new(1, Perbill::from_percent(30), 100);
+	assert_eq!(bid.remove_coins(10), Ok(33));
+	assert_eq!(bid.quantity, 67);
+
+	// 1 / 70% = 1.428.. --> 1 coin buys 1.428.. payout
+	let mut bid = BidT::new(1, Perbill::from_percent(70), 100);
+	assert_eq!(bid.remove_coins(1), Ok(1));
+	assert_eq!(bid.quantity, 99);
+
+	for percent in 1..=100 {
+		let price = Perbill::from_percent(percent);
+		for coins in 1..50 {
+			let mut bid = BidT::new(1, price, 10_000);
+			let removed = bid.remove_coins(coins).expect("quantity is big enough");
+			// the payout credited is never worth more than the coins paid
+			assert!(removed * u64::from(percent) <= coins * 100);
+			// but at most one coin of payout is lost to rounding
+			assert!((removed + 1) * u64::from(percent) > coins * 100);
+			assert_eq!(bid.quantity, 10_000 - removed);
+		}
+	}
+}
+
+#[test]
+fn payment_does_not_overflow_for_huge_quantities() {
+	let max = u64::max_value();
+	assert_eq!(BidT::new(1, Perbill::from_percent(100), max).payment(), max);
+	assert_eq!(BidT::new(1, Perbill::zero(), max).payment(), 0);
+
+	let half = BidT::new(1, Perbill::from_percent(50), max).payment();
+	assert_ge!(half, max / 2);
+	assert_le!(half, max / 2 + 1);
+
+	// 99.9999999% of u64::MAX is smaller than u64::MAX by about a billionth of it
+	let almost = BidT::new(1, Perbill::from_parts(999_999_999), max).payment();
+	assert_lt!(almost, max);
+	assert_ge!(almost, max - max / 1_000_000_000 - 1);
+}
+
+#[test]
+fn remove_coins_fails_only_for_rounded_up_payments() {
+	// 15% * 1004 = 150.6 --> payment is rounded up to 151
+	let bid = BidT::new(1, Perbill::from_percent(15), 1004);
+	assert_eq!(bid.payment(), 151);
+	// but 151 / 15% = 1006.66.. > 1004
+	assert_eq!(bid.clone().remove_coins(151), Err(BidError::Underflow));
+	assert_eq!(bid.clone().remove_coins(150), Ok(1000));
+
+	for percent in 10..=100 {
+		let price = Perbill::from_percent(percent);
+		for quantity in 1000..1050 {
+			let bid = BidT::new(1, price, quantity);
+			for coins in 0..bid.payment {
+				assert_ok!(bid.clone().remove_coins(coins));
+			}
+		}
+	}
+
+	// large amounts do not overflow
+	let mut bid = BidT::new(1, Perbill::from_parts(800_000_001), u64::max_value());
+	assert_ok!(bid.remove_coins(u64::max_value() / 2));
+}
+
+#[test]
+fn bids_are_sorted_highest_to_lowest() {
+	new_test_ext().execute_with(|| {
+		let bid_amount = 5 * BaseUnit::get();
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(33), bid_amount));
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), bid_amount));
+
+		let bids = Stablecoin::bond_bids();
+		let prices: Vec<_> = bids.into_iter().map(|Bid { price, .. }| price).collect();
+		// largest bid is stored last so we can pop
+		assert_eq!(
+			prices,
+			vec![
+				Perbill::from_percent(25),
+				Perbill::from_percent(33),
+				Perbill::from_percent(50),
+			]
+		);
+	});
+}
+
+#[test]
+fn amount_of_bids_is_limited() {
+	new_test_ext().execute_with(|| {
+		let bid_amount = 5 * BaseUnit::get();
+		for _i in 0..(2 * MaximumBids::get()) {
+			Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
+		}
+
+		assert_eq!(Stablecoin::bond_bids().len() as u64, MaximumBids::get());
+	});
+}
+
+#[test]
+fn truncated_bids_are_refunded() {
+	new_test_ext_with(vec![1]).execute_with(|| {
+		let price = Perbill::from_percent(25);
+		let quantity = BaseUnit::get();
+		for _i in 0..MaximumBids::get() {
+			assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		}
+		let higher_price = Perbill::from_percent(30);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), higher_price, quantity));
+
+		assert_eq!(Stablecoin::bond_bids().len() as u64, MaximumBids::get());
+		let expected =
+			InitialSupply::get() - price * quantity * (MaximumBids::get() as u64 - 1) - higher_price * quantity;
+		assert_eq!(Stablecoin::get_balance(1), expected);
+	});
+}
+
+#[test]
+fn bid_actions_are_rate_limited_per_block() {
+	use frame_support::traits::OnFinalize;
+
+	MaxBidActionsPerBlock::set(3);
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let price = Perbill::from_percent(50);
+		let quantity = BaseUnit::get();
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		// failed calls are not counted
+		assert_noop!(
+			Stablecoin::cancel_one_bid_at(Origin::signed(1), Perbill::from_percent(60), quantity),
+			Error::<Test>::BidNotFound
+		);
+		assert_ok!(Stablecoin::bid_for_bonds(Origin::signed(1), vec![(price, quantity)]));
+		assert_ok!(Stablecoin::cancel_one_bid_at(Origin::signed(1), price, quantity));
+
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), price, quantity),
+			Error::<Test>::RateLimited
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bonds(Origin::signed(1), vec![(price, quantity)]),
+			Error::<Test>::RateLimited
+		);
+		assert_noop!(Stablecoin::cancel_all_bids(Origin::signed(1)), Error::<Test>::RateLimited);
+		// other accounts are not affected
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), price, quantity));
+
+		// the counts are removed at the end of the block
+		assert_eq!(Stablecoin::bid_actions(1, 1), 3);
+		Stablecoin::on_finalize(1);
+		assert_eq!(Stablecoin::bid_actions(1, 1), 0);
+		assert_eq!(Stablecoin::bid_actions(1, 2), 0);
+
+		// the limit resets in the next block
+		System::set_block_number(2);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		assert_eq!(Stablecoin::bids_of(&1).len(), 2);
+	});
+}
+
+#[test]
+fn average_bid_price_test() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Stablecoin::average_bid_price(), None);
+
+		let quantity = BaseUnit::get();
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(50), quantity));
+		assert_eq!(Stablecoin::average_bid_price(), Some(Perbill::from_percent(50)));
+
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), Perbill::from_percent(80), 2 * quantity));
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(3), Perbill::from_percent(20), quantity));
+		// (50% * 1 + 80% * 2 + 20% * 1) / 4 = 57.5%
+		assert_eq!(Stablecoin::average_bid_price(), Some(Perbill::from_parts(575_000_000)));
+
+		// the average is rounded down
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(4), Perbill::from_percent(30), quantity + 1));
+		// (50% * 1000 + 80% * 2000 + 20% * 1000 + 30% * 1001) / 5001 = 51.99..%
+		let expected = (575_000_000u128 * 4000 + 300_000_000 * 1001) / 5001;
+		assert_eq!(Stablecoin::average_bid_price(), Some(Perbill::from_parts(expected as u32)));
+	});
+}
+
+#[test]
+fn expired_bids_are_refunded_and_removed() {
+	use frame_support::traits::OnInitialize;
+
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let price = Perbill::from_percent(50);
+		let quantity = BaseUnit::get();
+		let balance = Stablecoin::get_balance(1);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		let expiration = 1 + BidExpirationPeriod::get();
+		assert_eq!(Stablecoin::bids_of(&1)[0].expiration, expiration);
+
+		System::set_block_number(expiration - 1);
+		Stablecoin::on_initialize(expiration - 1);
+		assert_eq!(Stablecoin::bids_of(&1).len(), 1);
+		assert_eq!(Stablecoin::get_balance(1), balance - price * quantity);
+
+		System::set_block_number(expiration);
+		Stablecoin::on_initialize(expiration);
+		assert!(Stablecoin::bond_bids().is_empty());
+		assert_eq!(Stablecoin::get_balance(1), balance);
+		assert_eq!(Stablecoin::reserved_balance(1), 0);
+		assert_eq!(Stablecoin::total_locked_in_bids(), 0);
+		assert!(stablecoin_events().contains(&RawEvent::BidExpired(1, price, quantity)));
+	});
+}
+
+#[test]
+fn full_queue_rejects_all_bids_with_reject_new_policy() {
+	EvictionPolicy::set(BidEvictionPolicy::RejectNew);
+	new_test_ext().execute_with(|| {
+		let price = Perbill::from_percent(25);
+		let quantity = BaseUnit::get();
+		for _i in 0..MaximumBids::get() {
+			assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), price, quantity));
+		}
+		let balance_before = Stablecoin::get_balance(1);
+
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(90), quantity),
+			Error::<Test>::BidQueueFull
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bonds(Origin::signed(1), vec![(Perbill::from_percent(90), quantity)]),
+			Error::<Test>::BidQueueFull
+		);
+		assert_eq!(Stablecoin::get_balance(1), balance_before);
+		assert_eq!(Stablecoin::bids_of(&2).len() as u64, MaximumBids::get());
+
+		// there is room again after cancelling a bid
+		assert_ok!(Stablecoin::cancel_one_bid_at(Origin::signed(2), price, quantity));
+		assert_ok!(Stablecoin::bid_for_bond(
+			Origin::signed(1),
+			Perbill::from_percent(90),
+			quantity
+		));
+	});
+}
+
+#[test]
+fn full_queue_rejects_bids_not_above_the_lowest() {
+	new_test_ext().execute_with(|| {
+		let price = Perbill::from_percent(25);
+		let quantity = BaseUnit::get();
+		for _i in 0..MaximumBids::get() {
+			assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), price, quantity));
+		}
+		let balance_before = Stablecoin::get_balance(1);
+
+		// bidding at the lowest price does not evict other bids
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), price, quantity),
+			Error::<Test>::BidQueueFull
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bonds(
+				Origin::signed(1),
+				vec![(Perbill::from_percent(30), quantity), (price, quantity)]
+			),
+			Error::<Test>::BidQueueFull
+		);
+		assert_eq!(Stablecoin::get_balance(1), balance_before);
+		assert_eq!(Stablecoin::bids_of(&2).len() as u64, MaximumBids::get());
+
+		// a higher bid evicts (and refunds) the lowest bid
+		let higher_price = Perbill::from_percent(30);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), higher_price, quantity));
+		assert_eq!(Stablecoin::get_balance(1), balance_before - higher_price * quantity);
+		assert_eq!(Stablecoin::bids_of(&2).len() as u64, MaximumBids::get() - 1);
+	});
+}
+
+#[test]
+fn bids_per_account_are_limited() {
+	MaxBidsPerAccount::set(3);
+	new_test_ext().execute_with(|| {
+		let price = Perbill::from_percent(25);
+		let quantity = BaseUnit::get();
+		for _i in 0..3 {
+			assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		}
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), price, quantity),
+			Error::<Test>::TooManyBidsForAccount
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bonds(Origin::signed(1), vec![(price, quantity)]),
+			Error::<Test>::TooManyBidsForAccount
+		);
+		// other accounts can still bid
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), price, quantity));
+		assert_noop!(
+			Stablecoin::bid_for_bonds(Origin::signed(2), vec![(price, quantity); 3]),
+			Error::<Test>::TooManyBidsForAccount
+		);
+		assert_ok!(Stablecoin::bid_for_bonds(Origin::signed(2), vec![(price, quantity); 2]));
+		assert_eq!(Stablecoin::bids_of(&1).len(), 3);
+		assert_eq!(Stablecoin::bids_of(&2).len(), 3);
+	});
+}
+
+#[test]
+fn set_minimum_bond_price_test() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Stablecoin::minimum_bond_price(), MinimumBondPrice::get());
+		assert_noop!(
+			Stablecoin::set_minimum_bond_price(Origin::signed(1), Perbill::from_percent(50)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stablecoin::set_minimum_bond_price(system::RawOrigin::Root.into(), Perbill::from_percent(0)),
+			Error::<Test>::ZeroMinimumBondPrice
+		);
+		assert_noop!(
+			Stablecoin::set_minimum_bond_price(system::RawOrigin::Root.into(), Perbill::from_percent(100)),
+			Error::<Test>::BondPriceOver100Percent
+		);
+
+		assert_ok!(Stablecoin::set_minimum_bond_price(
+			system::RawOrigin::Root.into(),
+			Perbill::from_percent(50)
+		));
+		assert_eq!(Stablecoin::minimum_bond_price(), Perbill::from_percent(50));
+
+		let quantity = BaseUnit::get();
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(40), quantity),
+			Error::<Test>::BondPriceTooLow
+		);
+		assert_ok!(Stablecoin::bid_for_bond(
+			Origin::signed(1),
+			Perbill::from_percent(60),
+			quantity
+		));
+	});
+}
+
+#[test]
+fn minimum_bid_quantity_test() {
+	MinimumBidQuantity::set(5 * TEST_BASE_UNIT);
+	new_test_ext().execute_with(|| {
+		let price = Perbill::from_percent(50);
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), price, 5 * BaseUnit::get() - 1),
+			Error::<Test>::BondQuantityTooLow
+		);
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(1), price, BaseUnit::get()),
+			Error::<Test>::BondQuantityTooLow
+		);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, 5 * BaseUnit::get()));
+	});
+}
+
+#[test]
+fn bid_for_bond_limited_test() {
+	new_test_ext().execute_with(|| {
+		let balance = Stablecoin::get_balance(1);
+		// 33% of 1_001 = 330.33 --> rounds to 330
+		let price = Perbill::from_percent(33);
+		let quantity = TEST_BASE_UNIT + 1;
+		assert_noop!(
+			Stablecoin::bid_for_bond_limited(Origin::signed(1), price, quantity, 329),
+			Error::<Test>::PaymentExceedsLimit
+		);
+		assert_eq!(Stablecoin::get_balance(1), balance);
+		assert_eq!(Stablecoin::reserved_balance(1), 0);
+
+		assert_ok!(Stablecoin::bid_for_bond_limited(Origin::signed(1), price, quantity, 330));
+		assert_eq!(bid_terms(Stablecoin::bond_bids()), vec![(1, price, quantity)]);
+		assert_eq!(Stablecoin::get_balance(1), balance - 330);
+		assert_eq!(Stablecoin::reserved_balance(1), 330);
+	});
+}
+
+#[test]
+fn bid_for_bonds_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let quantity = BaseUnit::get();
+		let balance_before = Stablecoin::get_balance(1);
+		let bids = vec![
+			(Perbill::from_percent(50), quantity),
+			(Perbill::from_percent(60), 2 * quantity),
+			(Perbill::from_percent(70), quantity),
+		];
+		assert_ok!(Stablecoin::bid_for_bonds(Origin::signed(1), bids.clone()));
+
+		assert_eq!(
+			Stablecoin::get_balance(1),
+			balance_before - (500 + 1_200 + 700)
+		);
+		assert_eq!(Stablecoin::bond_bids().len(), 3);
+		let new_bid_events: Vec<_> = stablecoin_events()
+			.into_iter()
+			.filter(|e| matches!(e, RawEvent::NewBid(..)))
+			.collect();
+		assert_eq!(
+			new_bid_events,
+			bids.into_iter()
+				.map(|(price, quantity)| RawEvent::NewBid(1, price, quantity))
+				.collect::<Vec<_>>()
+		);
+	});
+}
+
+#[test]
+fn equal_bids_are_converted_first_in_first_out() {
+	new_test_ext().execute_with(|| {
+		let price = Perbill::from_percent(50);
+		let quantity = BaseUnit::get();
+		for acc in [3, 1, 4, 2].iter() {
+			assert_ok!(Stablecoin::bid_for_bond(Origin::signed(*acc), price, quantity));
+		}
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(5), Perbill::from_percent(40), quantity));
+
+		assert_eq!(
+			bid_terms(Stablecoin::top_bids(4)),
+			vec![(3, price, quantity), (1, price, quantity), (4, price, quantity), (2, price, quantity)]
+		);
+
+		// convert the payment of three bids
+		let amount = 3 * (price * quantity);
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), amount), amount);
+		let (start, length) = Stablecoin::bonds_range();
+		let bond_accounts: Vec<AccountId> = (0..length)
+			.map(|i| Stablecoin::get_bond(start.wrapping_add(i)).account)
+			.collect();
+		assert_eq!(bond_accounts, vec![3, 1, 4]);
+		let remaining: Vec<AccountId> = Stablecoin::bond_bids().into_iter().map(|bid| bid.account).collect();
+		assert_eq!(remaining, vec![5, 2]);
+	});
+}
+
+#[test]
+fn top_bids_test() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		for percent in [30, 90, 50, 70, 40].iter() {
+			Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(*percent), quantity));
+		}
+
+		assert_eq!(
+			bid_terms(Stablecoin::top_bids(3)),
+			vec![
+				(1, Perbill::from_percent(90), quantity),
+				(1, Perbill::from_percent(70), quantity),
+				(1, Perbill::from_percent(50), quantity),
+			]
+		);
+		assert_eq!(Stablecoin::top_bids(0), vec![]);
+		assert_eq!(Stablecoin::top_bids(10).len(), 5);
+		assert_eq!(Stablecoin::bond_bids().len(), 5, "bid queue should be unchanged");
+	});
+}
+
+#[test]
+fn locked_in_bids_test() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let balance_before = Stablecoin::get_balance(1);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(50), quantity));
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), Perbill::from_percent(55), quantity));
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(75), 3 * quantity));
+
+		let bids = Stablecoin::bids_of(&1);
+		assert_eq!(
+			bid_terms(bids),
+			vec![
+				(1, Perbill::from_percent(50), quantity),
+				(1, Perbill::from_percent(75), 3 * quantity),
+			]
+		);
+		assert_eq!(
+			Stablecoin::locked_in_bids(&1),
+			balance_before - Stablecoin::get_balance(1)
+		);
+		assert_eq!(Stablecoin::locked_in_bids(&3), 0);
+	});
+}
+
+#[test]
+fn bid_payments_are_reserved() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let balance_before = Stablecoin::get_balance(1);
+		let supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(50), quantity));
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(80), quantity));
+
+		assert_eq!(Stablecoin::reserved_balance(1), 500 + 800);
+		assert_eq!(Stablecoin::get_balance(1), balance_before - 500 - 800);
+		assert_eq!(<Stablecoin as BasicCurrency<AccountId>>::free_balance(&1), balance_before - 1_300);
+		assert_eq!(<Stablecoin as BasicCurrency<AccountId>>::total_balance(&1), balance_before);
+
+		// converting a bid burns the reserved payment
+		assert_ok!(Stablecoin::contract_supply(supply, 800), 800);
+		assert_eq!(Stablecoin::reserved_balance(1), 500);
+		assert_eq!(Stablecoin::get_balance(1), balance_before - 1_300);
+
+		// cancelling a bid releases the reserved payment
+		assert_ok!(Stablecoin::cancel_all_bids(Origin::signed(1)));
+		assert_eq!(Stablecoin::reserved_balance(1), 0);
+		assert!(!<Reserved<Test>>::contains_key(1));
+		assert_eq!(Stablecoin::get_balance(1), balance_before - 800);
+	});
+}
+
+#[test]
+fn partial_bid_conversion_keeps_reserve_of_remaining_bid() {
+	new_test_ext().execute_with(|| {
+		let quantity = 2 * BaseUnit::get();
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(50), quantity));
+		assert_eq!(Stablecoin::reserved_balance(1), 1_000);
+
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), 400), 400);
+
+		let remaining: Coins = Stablecoin::bids_of(&1).iter().map(|bid| bid.payment).sum();
+		assert_eq!(remaining, 600);
+		assert_eq!(Stablecoin::reserved_balance(1), remaining);
+	});
+}
+
+#[test]
+fn partial_bid_conversion_burns_exactly_the_contraction() {
+	new_test_ext().execute_with(|| {
+		let total_coins = || -> Coins {
+			(0..20)
+				.map(|acc| Stablecoin::get_balance(acc) + Stablecoin::reserved_balance(acc))
+				.sum()
+		};
+		assert_eq!(total_coins(), Stablecoin::coin_supply());
+		// 70% * 1_000 = 700
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(70), 1_000));
+
+		// 2 / 70% = 2.86 --> 2 payout for 2 Coins, leaving 998 quantity for 698 Coins
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), 2), 2);
+
+		let bids = Stablecoin::bids_of(&1);
+		assert_eq!(bids.len(), 1);
+		assert_eq!(bids[0].quantity(), 998);
+		assert_eq!(bids[0].payment(), 698);
+		assert_eq!(Stablecoin::reserved_balance(1), 698);
+		assert_eq!(Stablecoin::total_locked_in_bids(), 698);
+		assert_eq!(total_coins(), Stablecoin::coin_supply());
+	});
+}
+
+#[test]
+fn migrate_reserves_bid_payments() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		// bids encoded like the version 3 `Bid` struct
+		let old_bids: Vec<(AccountId, Perbill, Coins)> = vec![
+			(1, Perbill::from_percent(50), quantity),
+			(2, Perbill::from_percent(60), quantity),
+		];
+		unhashed::put(&Stablecoin::storage_key(b"BondBids"), &old_bids);
+		<StorageVersion>::put(3);
+
+		Stablecoin::migrate();
+
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		assert_eq!(Stablecoin::reserved_balance(1), 500);
+		assert_eq!(Stablecoin::reserved_balance(2), 600);
+		let mut payments: Vec<Coins> = Stablecoin::bond_bids().iter().map(|bid| bid.payment).collect();
+		payments.sort();
+		assert_eq!(payments, vec![500, 600]);
+	});
+}
+
+#[test]
+fn migrate_sequences_bids() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let price = Perbill::from_percent(50);
+		// bids encoded like the version 4 `Bid` struct
+		let old_bids: Vec<(AccountId, Perbill, Coins, Coins)> =
+			vec![(1, price, quantity, 500), (2, price, 2 * quantity, 1_000)];
+		unhashed::put(&Stablecoin::storage_key(b"BondBids"), &old_bids);
+		<StorageVersion>::put(4);
+
+		Stablecoin::migrate();
+
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		assert_eq!(
+			bid_terms(Stablecoin::top_bids(2)),
+			vec![(1, price, quantity), (2, price, 2 * quantity)]
+		);
+		assert_eq!(Stablecoin::next_bid_sequence(), 2);
+	});
+}
+
+#[test]
+fn migrate_bids_add_expiration() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(7);
+		let quantity = BaseUnit::get();
+		let price = Perbill::from_percent(50);
+		// bids encoded like the version 5 `Bid` struct
+		let old_bids: Vec<(AccountId, Perbill, Coins, u64, Coins)> =
+			vec![(1, price, quantity, 0, 500), (2, price, 2 * quantity, 1, 1_000)];
+		unhashed::put(&Stablecoin::storage_key(b"BondBids"), &old_bids);
+		<StorageVersion>::put(5);
+
+		Stablecoin::migrate();
+
+		assert_eq!(Stablecoin::storage_version(), CURRENT_STORAGE_VERSION);
+		let bids = Stablecoin::top_bids(2);
+		assert_eq!(
+			bid_terms(bids.clone()),
+			vec![(1, price, quantity), (2, price, 2 * quantity)]
+		);
+		assert!(bids.iter().all(|bid| bid.expiration == 7 + BidExpirationPeriod::get()));
+	});
+}
+
+#[test]
+fn circulating_supply_test() {
+	new_test_ext().execute_with(|| {
+		let supply = Stablecoin::coin_supply();
+		assert_eq!(Stablecoin::circulating_supply(), supply);
+
+		let quantity = BaseUnit::get();
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), Perbill::from_percent(50), quantity));
+		assert_ok!(Stablecoin::bid_for_bonds(
+			Origin::signed(2),
+			vec![(Perbill::from_percent(60), quantity), (Perbill::from_percent(70), quantity)]
+		));
+		assert_eq!(Stablecoin::circulating_supply(), supply - 500 - 600 - 700);
+
+		assert_ok!(Stablecoin::cancel_bids_at_or_below(Origin::signed(2), Perbill::from_percent(60)));
+		assert_eq!(Stablecoin::circulating_supply(), supply - 500 - 700);
+
+		// converting a bid partially only unlocks the converted part
+		assert_ok!(Stablecoin::contract_supply(supply, 350), 350);
+		assert_eq!(Stablecoin::coin_supply(), supply - 350);
+		assert_eq!(Stablecoin::circulating_supply(), supply - 350 - 500 - 350);
+		let locked: Coins = Stablecoin::bond_bids().iter().map(|bid| bid.payment).sum();
+		assert_eq!(Stablecoin::total_locked_in_bids(), locked);
+	});
+}
+
+#[test]
+fn bid_for_bonds_is_all_or_nothing() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let balance = Stablecoin::get_balance(1);
+		// invalid price
+		assert_noop!(
+			Stablecoin::bid_for_bonds(
+				Origin::signed(1),
+				vec![(Perbill::from_percent(50), quantity), (Perbill::from_percent(5), quantity)]
+			),
+			Error::<Test>::BondPriceTooLow
+		);
+		// quantity below base unit
+		assert_noop!(
+			Stablecoin::bid_for_bonds(
+				Origin::signed(1),
+				vec![(Perbill::from_percent(50), quantity), (Perbill::from_percent(50), quantity - 1)]
+			),
+			Error::<Test>::BondQuantityTooLow
+		);
+		// cannot pay for all bids
+		assert_noop!(
+			Stablecoin::bid_for_bonds(
+				Origin::signed(1),
+				vec![(Perbill::from_percent(50), balance), (Perbill::from_percent(50), balance)]
+			),
+			Error::<Test>::InsufficientBalance
+		);
+		// batch too large
+		assert_noop!(
+			Stablecoin::bid_for_bonds(
+				Origin::signed(1),
+				vec![(Perbill::from_percent(50), quantity); MaximumBids::get() as usize + 1]
+			),
+			Error::<Test>::TooManyBids
+		);
+	});
+}
+
+#[test]
+fn cancel_all_bids_test() {
+	new_test_ext().execute_with(|| {
+		let bid_amount = 5 * BaseUnit::get();
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
+		Stablecoin::add_bid(Bid::new(2, Perbill::from_percent(33), bid_amount));
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), bid_amount));
+		Stablecoin::add_bid(Bid::new(3, Perbill::from_percent(50), bid_amount));
+		assert_eq!(Stablecoin::bond_bids().len(), 4);
+
+		assert_ok!(Stablecoin::cancel_all_bids(Origin::signed(1)));
+
+		let bids = Stablecoin::bond_bids();
+		assert_eq!(bids.len(), 2);
+		for bid in bids {
+			assert!(bid.account != 1);
+		}
+	});
+}
+
+#[test]
+fn cancel_bids_in_range_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let quantity = BaseUnit::get();
+		let balance = Stablecoin::get_balance(1);
+		for percent in &[20, 30, 40, 50, 60] {
+			assert_ok!(Stablecoin::bid_for_bond(
+				Origin::signed(1),
+				Perbill::from_percent(*percent),
+				quantity
+			));
+		}
+		assert_ok!(Stablecoin::bid_for_bond(
+			Origin::signed(2),
+			Perbill::from_percent(40),
+			quantity
+		));
+
+		assert_noop!(
+			Stablecoin::cancel_bids_in_range(
+				Origin::signed(1),
+				Perbill::from_percent(50),
+				Perbill::from_percent(30)
+			),
+			Error::<Test>::InvalidRange
+		);
+
+		let low = Perbill::from_percent(30);
+		let high = Perbill::from_percent(50);
+		assert_ok!(Stablecoin::cancel_bids_in_range(Origin::signed(1), low, high));
+		assert_eq!(
+			bid_terms(Stablecoin::bond_bids()),
+			vec![
+				(1, Perbill::from_percent(60), quantity),
+				(2, Perbill::from_percent(40), quantity),
+				(1, Perbill::from_percent(20), quantity),
+			]
+		);
+		// the payments for the remaining 60% and 20% bids are still reserved
+		assert_eq!(Stablecoin::get_balance(1), balance - Perbill::from_percent(80) * quantity);
+		assert_eq!(Stablecoin::reserved_balance(1), Perbill::from_percent(80) * quantity);
+		assert_eq!(
+			stablecoin_events().last(),
+			Some(&RawEvent::CancelledBidsInRange(1, low, high))
+		);
+	});
+}
+
+#[test]
+fn replace_bids_test() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let balance = Stablecoin::get_balance(1);
+		assert_ok!(Stablecoin::bid_for_bonds(
+			Origin::signed(1),
+			vec![
+				(Perbill::from_percent(30), quantity),
+				(Perbill::from_percent(40), quantity),
+				(Perbill::from_percent(60), quantity)
+			]
+		));
+
+		// an invalid new bid leaves the old bids in place
+		assert_noop!(
+			Stablecoin::replace_bids(
+				Origin::signed(1),
+				Perbill::from_percent(40),
+				vec![(Perbill::from_percent(50), quantity), (Perbill::from_percent(50), quantity - 1)]
+			),
+			Error::<Test>::BondQuantityTooLow
+		);
+
+		assert_ok!(Stablecoin::replace_bids(
+			Origin::signed(1),
+			Perbill::from_percent(40),
+			vec![(Perbill::from_percent(50), quantity), (Perbill::from_percent(45), quantity)]
+		));
+		assert_eq!(
+			bid_terms(Stablecoin::bond_bids()),
+			vec![
+				(1, Perbill::from_percent(60), quantity),
+				(1, Perbill::from_percent(50), quantity),
+				(1, Perbill::from_percent(45), quantity),
+			]
+		);
+		assert_eq!(Stablecoin::reserved_balance(1), 600 + 500 + 450);
+		assert_eq!(Stablecoin::get_balance(1), balance - 1_550);
+	});
+}
+
+#[test]
+fn replace_bids_uses_refunds() {
+	new_test_ext().execute_with(|| {
+		let balance = Stablecoin::get_balance(2);
+		let price = Perbill::from_percent(50);
+		// the bid locks 75% of the balance
+		let quantity = balance + balance / 2;
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), price, quantity));
+		assert_noop!(
+			Stablecoin::bid_for_bond(Origin::signed(2), Perbill::from_percent(60), quantity),
+			Error::<Test>::InsufficientBalance
+		);
+
+		// paying for the new bid is only possible with the refund of the old one
+		assert_ok!(Stablecoin::replace_bids(
+			Origin::signed(2),
+			price,
+			vec![(Perbill::from_percent(60), quantity)]
+		));
+		assert_eq!(
+			bid_terms(Stablecoin::bond_bids()),
+			vec![(2, Perbill::from_percent(60), quantity)]
+		);
+		assert_eq!(Stablecoin::reserved_balance(2), Perbill::from_percent(60) * quantity);
+		assert_eq!(Stablecoin::get_balance(2), balance - Perbill::from_percent(60) * quantity);
+	});
+}
+
+#[test]
+fn cancel_one_bid_at_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let price = Perbill::from_percent(50);
+		let quantity = BaseUnit::get();
+		let balance_before = Stablecoin::get_balance(1);
+		for _ in 0..3 {
+			assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		}
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(2), price, quantity));
+
+		assert_ok!(Stablecoin::cancel_one_bid_at(Origin::signed(1), price, quantity));
+
+		assert_eq!(Stablecoin::bids_of(&1).len(), 2, "exactly one bid should be cancelled");
+		assert_eq!(Stablecoin::bids_of(&2).len(), 1);
+		assert_eq!(Stablecoin::get_balance(1), balance_before - 2 * (price * quantity));
+		assert_eq!(stablecoin_events().last(), Some(&RawEvent::CancelledBid(1, price, quantity)));
+
+		assert_noop!(
+			Stablecoin::cancel_one_bid_at(Origin::signed(1), price, 2 * quantity),
+			Error::<Test>::BidNotFound
+		);
+		assert_noop!(
+			Stablecoin::cancel_one_bid_at(Origin::signed(3), price, quantity),
+			Error::<Test>::BidNotFound
+		);
+	});
+}
+
+#[test]
+fn cancel_selected_bids_test() {
+	new_test_ext().execute_with(|| {
+		let bid_amount = 5 * BaseUnit::get();
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(25), bid_amount));
+		Stablecoin::add_bid(Bid::new(2, Perbill::from_percent(33), bid_amount));
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(45), bid_amount));
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), bid_amount));
+		Stablecoin::add_bid(Bid::new(3, Perbill::from_percent(55), bid_amount));
+		assert_eq!(Stablecoin::bond_bids().len(), 5);
+
+		assert_ok!(Stablecoin::cancel_bids_at_or_below(
+			Origin::signed(1),
+			Perbill::from_percent(45)
+		));
+
+		let bids = Stablecoin::bond_bids();
+		assert_eq!(bids.len(), 3);
+		let bids: Vec<(_, _)> = bids
+			.into_iter()
+			.map(|Bid { account, price, .. }| (account, price))
+			.collect();
+		// highest bid is last so we can pop
+		assert_eq!(
+			bids,
+			vec![
+				(2, Perbill::from_percent(33)),
+				(1, Perbill::from_percent(50)),
+				(3, Perbill::from_percent(55)),
+			]
+		);
+	});
+}
+
+#[test]
+fn cancel_bids_at_or_above_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let bid_amount = 5 * BaseUnit::get();
+		for (acc, percent) in [(1, 25), (2, 33), (1, 45), (1, 50), (3, 55)].iter() {
+			assert_ok!(Stablecoin::bid_for_bond(
+				Origin::signed(*acc),
+				Perbill::from_percent(*percent),
+				bid_amount
+			));
+		}
+		let balance_before = Stablecoin::get_balance(1);
+
+		assert_ok!(Stablecoin::cancel_bids_at_or_above(
+			Origin::signed(1),
+			Perbill::from_percent(45)
+		));
+
+		let bids: Vec<(_, _)> = Stablecoin::bond_bids()
+			.into_iter()
+			.map(|Bid { account, price, .. }| (account, price))
+			.collect();
+		assert_eq!(
+			bids,
+			vec![
+				(1, Perbill::from_percent(25)),
+				(2, Perbill::from_percent(33)),
+				(3, Perbill::from_percent(55)),
+			]
+		);
+		// the cancelled bids are refunded
+		assert_eq!(Stablecoin::get_balance(1), balance_before + 2_250 + 2_500);
+		assert_eq!(
+			stablecoin_events().last(),
+			Some(&RawEvent::CancelledBidsAbove(1, Perbill::from_percent(45)))
+		);
+	});
+}
+
+// ------------------------------------------------------------
+// bonds
+#[test]
+fn adding_bonds() {
+	new_test_ext().execute_with(|| {
+		let payout = Fixed64::from_rational(20, 100).saturated_multiply_accumulate(BaseUnit::get());
+		add_bond(Stablecoin::new_bond(3, payout, test_bond_price()));
+
+		let (start, length) = Stablecoin::bonds_range();
+		assert_eq!(length, 1);
+		let bond = &Stablecoin::get_bond(start);
+		assert_eq!(bond.expiration, System::block_number() + ExpirationPeriod::get());
+	})
+}
+
+#[test]
+fn bonds_of_test() {
+	new_test_ext().execute_with(|| {
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(2, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(3, 2 * payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(2, 3 * payout, test_bond_price()));
+
+		let bonds: Vec<(BondIndex, Coins)> = Stablecoin::bonds_of(&2)
+			.into_iter()
+			.map(|(index, bond)| (index, bond.payout))
+			.collect();
+		assert_eq!(bonds, vec![(0, payout), (2, 3 * payout)]);
+		assert!(Stablecoin::bonds_of(&4).is_empty());
+	})
+}
+
+#[test]
+fn bonds_are_unindexed_when_paid_out() {
+	new_test_ext().execute_with(|| {
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(2, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(3, 2 * payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(2, 3 * payout, test_bond_price()));
+		assert_eq!(Stablecoin::bond_indices_of(2), vec![0, 2]);
+		assert_eq!(Stablecoin::bond_indices_of(3), vec![1]);
+
+		// only pays out the first bond
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), payout));
+		assert_eq!(Stablecoin::bond_indices_of(2), vec![2]);
+		let bonds: Vec<(BondIndex, Coins)> = Stablecoin::bonds_of(&2)
+			.into_iter()
+			.map(|(index, bond)| (index, bond.payout))
+			.collect();
+		assert_eq!(bonds, vec![(2, 3 * payout)]);
+
+		// pays out the second bond and part of the third
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 3 * payout));
+		assert!(Stablecoin::bond_indices_of(3).is_empty());
+		assert_eq!(Stablecoin::bond_indices_of(2), vec![2]);
+		assert_eq!(Stablecoin::bonds_of(&2)[0].1.payout, 2 * payout);
+	})
+}
+
+#[test]
+fn expire_bonds() {
+	new_test_ext_with(vec![1]).execute_with(|| {
+		let acc = 3;
+		let prev_acc_balance = Stablecoin::get_balance(acc);
+		let payout = Fixed64::from_rational(20, 100).saturated_multiply_accumulate(BaseUnit::get());
+		add_bond(Stablecoin::new_bond(acc, payout, test_bond_price()));
+
+		let (start, length) = Stablecoin::bonds_range();
+		assert_eq!(length, 1);
+		let bond = &Stablecoin::get_bond(start);
+		assert_eq!(bond.expiration, System::block_number() + ExpirationPeriod::get());
+
+		let prev_supply = Stablecoin::coin_supply();
+		// set blocknumber past expiration time
+		System::set_block_number(System::block_number() + ExpirationPeriod::get());
+		assert_ok!(Stablecoin::expand_supply(prev_supply, 42));
+		let acc_balance = Stablecoin::get_balance(acc);
+		assert_eq!(
+			prev_acc_balance, acc_balance,
+			"account balance should not change as the bond expired"
+		);
+		assert_eq!(
+			prev_supply + 42,
+			Stablecoin::coin_supply(),
+			"coin supply should have increased"
+		);
+	});
+}
+
+#[test]
+fn bonds_queue_wraps_around_index() {
+	new_test_ext().execute_with(|| {
+		let start = BondIndex::max_value() - 2;
+		<BondsRange>::put((start, 0));
+		let payout = BaseUnit::get();
+		for account in 11..16 {
+			add_bond(Stablecoin::new_bond(account, payout, test_bond_price()));
+		}
+		assert_eq!(Stablecoin::bonds_range(), (start, 5));
+		let accounts: Vec<AccountId> = Stablecoin::bonds().into_iter().map(|bond| bond.account).collect();
+		assert_eq!(accounts, vec![11, 12, 13, 14, 15]);
+		assert_eq!(Stablecoin::bond_indices_of(11), vec![start]);
+		assert_eq!(Stablecoin::bond_indices_of(13), vec![BondIndex::max_value()]);
+		assert_eq!(Stablecoin::bond_indices_of(14), vec![0]);
+		assert_eq!(Stablecoin::bond_indices_of(15), vec![1]);
+
+		// bonds are paid out in order across the wrap
+		let prev_supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_supply(prev_supply, 4 * payout));
+		for account in 11..15 {
+			assert_eq!(Stablecoin::get_balance(account), payout);
+			assert!(Stablecoin::bonds_of(&account).is_empty());
+		}
+		assert_eq!(Stablecoin::get_balance(15), 0);
+		assert_eq!(Stablecoin::bonds_range(), (1, 1));
+		assert_eq!(Stablecoin::bonds_of(&15), vec![(1, Stablecoin::get_bond(1))]);
+	})
+}
+
+#[test]
+fn bond_bonus_is_added_to_payout() {
+	BondBonus::set(Perbill::from_percent(10));
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let price = Perbill::from_percent(80);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		let balance = Stablecoin::get_balance(1);
+
+		let supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::contract_supply(supply, price * quantity), price * quantity);
+		let bonds = Stablecoin::bonds();
+		assert_eq!(bonds.len(), 1);
+		assert_eq!(bonds[0].payout, quantity + quantity / 10);
+
+		// the bonus is minted on payout
+		let supply = Stablecoin::coin_supply();
+		let payout = quantity + quantity / 10;
+		assert_ok!(Stablecoin::expand_supply(supply, payout));
+		assert!(Stablecoin::bonds().is_empty());
+		assert_eq!(Stablecoin::get_balance(1), balance + payout);
+		assert_eq!(Stablecoin::coin_supply(), supply + payout);
+	});
+}
+
+#[test]
+fn bond_count_test() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Stablecoin::bond_count(), 0);
+		// start close to the end of the index range to wrap around
+		<BondsRange>::put((BondIndex::max_value() - 1, 0));
+		let payout = BaseUnit::get();
+		for account in 11..16 {
+			add_bond(Stablecoin::new_bond(account, payout, test_bond_price()));
+		}
+		assert_eq!(Stablecoin::bond_count(), 5);
+
+		let prev_supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_supply(prev_supply, 3 * payout));
+		assert_eq!(Stablecoin::bond_count(), 2);
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 2 * payout));
+		assert_eq!(Stablecoin::bond_count(), 0);
+	})
+}
+
+#[test]
+fn custom_bond_expiration() {
+	ShrinkingExpiration::set(true);
+	new_test_ext().execute_with(|| {
+		System::set_block_number(10);
+		let payout = BaseUnit::get();
+		for account in 11..14 {
+			let bond = Stablecoin::new_bond(account, payout, test_bond_price());
+			add_bond(bond);
+		}
+		let expirations: Vec<BlockNumber> = (0..3).map(|i| Stablecoin::get_bond(i).expiration).collect();
+		let period = ExpirationPeriod::get();
+		assert_eq!(expirations, vec![10 + period, 10 + period - 1, 10 + period - 2]);
+	});
+}
+
+#[test]
+fn purge_expired_bonds_test() {
+	new_test_ext().execute_with(|| {
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(2, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(3, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(4, payout, test_bond_price()));
+		System::set_block_number(50);
+		add_bond(Stablecoin::new_bond(5, payout, test_bond_price()));
+
+		// nothing expired yet
+		assert_eq!(Stablecoin::purge_expired_bonds(10), 0);
+		assert_eq!(Stablecoin::bonds_range().1, 4);
+
+		System::set_block_number(ExpirationPeriod::get());
+		// the amount of removed bonds is limited
+		assert_eq!(Stablecoin::purge_expired_bonds(2), 2);
+		assert_eq!(Stablecoin::bonds_range().1, 2);
+		assert_eq!(Stablecoin::purge_expired_bonds(10), 1);
+		assert_eq!(Stablecoin::bonds_range().1, 1);
+		assert!(Stablecoin::bonds_of(&4).is_empty());
+		assert_eq!(Stablecoin::bonds_of(&5).len(), 1);
+
+		System::set_block_number(50 + ExpirationPeriod::get());
+		assert_eq!(Stablecoin::purge_expired_bonds(10), 1);
+		assert_eq!(Stablecoin::bonds_range().1, 0);
+		assert!(Stablecoin::bond_indices_of(5).is_empty());
+	})
+}
+
+#[test]
+fn maximum_bonds_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payout = BaseUnit::get();
+		let max = MaximumBonds::get();
+		for _ in 1..max {
+			add_bond(Stablecoin::new_bond(1, payout, test_bond_price()));
+		}
+		// pushing more bonds than there is room for is refused as a whole
+		assert_noop!(
+			Stablecoin::push_bonds(vec![
+				Stablecoin::new_bond(2, payout, test_bond_price()),
+				Stablecoin::new_bond(2, payout, test_bond_price())
+			]),
+			Error::<Test>::TooManyBonds
+		);
+		add_bond(Stablecoin::new_bond(2, payout, test_bond_price()));
+		assert_eq!(Stablecoin::bonds_transient().len(), max);
+		assert_noop!(
+			Stablecoin::push_bonds(vec![Stablecoin::new_bond(2, payout, test_bond_price())]),
+			Error::<Test>::TooManyBonds
+		);
+
+		// contraction refunds the bids it cannot convert to bonds while the queue is full
+		let bidder = 3;
+		let price = Perbill::from_percent(80);
+		let balance = Stablecoin::get_balance(bidder);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(bidder), price, payout));
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(bidder), price, payout));
+		let supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::contract_supply(supply, price * payout), 0);
+		assert_eq!(Stablecoin::coin_supply(), supply);
+		// only the bid covering the contraction is refunded
+		assert_eq!(Stablecoin::bond_bids().len(), 1);
+		assert_eq!(Stablecoin::get_balance(bidder), balance - price * payout);
+		assert_eq!(Stablecoin::total_locked_in_bids(), price * payout);
+		assert!(Stablecoin::bonds_of(&bidder).is_empty());
+		assert!(stablecoin_events().contains(&RawEvent::RefundedBid(bidder, price * payout)));
+		assert!(stablecoin_events().contains(&RawEvent::ContractionShortfall(price * payout, 0)));
+
+		// once there is room again the remaining bid is converted
+		System::set_block_number(1 + ExpirationPeriod::get());
+		assert_eq!(Stablecoin::purge_expired_bonds(1), 1);
+		assert_ok!(Stablecoin::contract_supply(supply, price * payout), price * payout);
+		assert!(Stablecoin::bond_bids().is_empty());
+		assert_eq!(Stablecoin::bonds_of(&bidder).len(), 1);
+	});
+}
+
+/// Add bonds for 11 (3 base units) and 12 (1 base unit) expiring at the same block and
+/// a later bond for 13 (1 base unit), then expand the supply by 2 base units.
+fn expand_with_same_expiration_bonds() {
+	let payout = BaseUnit::get();
+	add_bond(Stablecoin::new_bond(11, 3 * payout, test_bond_price()));
+	add_bond(Stablecoin::new_bond(12, payout, test_bond_price()));
+	System::set_block_number(2);
+	add_bond(Stablecoin::new_bond(13, payout, test_bond_price()));
+	assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 2 * payout));
+}
+
+#[test]
+fn fifo_bond_payout() {
+	new_test_ext().execute_with(|| {
+		let payout = BaseUnit::get();
+		expand_with_same_expiration_bonds();
+		// the first bond gets everything
+		assert_eq!(Stablecoin::get_balance(11), 2 * payout);
+		assert_eq!(Stablecoin::get_balance(12), 0);
+		assert_eq!(Stablecoin::bonds_of(&11)[0].1.payout, payout);
+		assert_eq!(Stablecoin::bonds_range(), (0, 3));
+	});
+}
+
+#[test]
+fn pro_rata_bond_payout() {
+	PayoutMode::set(BondPayoutMode::ProRata);
+	new_test_ext().execute_with(|| {
+		let payout = BaseUnit::get();
+		let prev_supply = Stablecoin::coin_supply();
+		expand_with_same_expiration_bonds();
+		// the bonds with the same expiration share the expansion
+		assert_eq!(Stablecoin::get_balance(11), 3 * payout / 2);
+		assert_eq!(Stablecoin::get_balance(12), payout / 2);
+		assert_eq!(Stablecoin::get_balance(13), 0);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + 2 * payout);
+		// the bonds keep their place in the queue
+		assert_eq!(Stablecoin::bonds_range(), (0, 3));
+		assert_eq!(Stablecoin::bonds_of(&11), vec![(0, Stablecoin::get_bond(0))]);
+		assert_eq!(Stablecoin::get_bond(0).payout, 3 * payout / 2);
+		assert_eq!(Stablecoin::bonds_of(&12), vec![(1, Stablecoin::get_bond(1))]);
+		assert_eq!(Stablecoin::get_bond(1).payout, payout / 2);
+
+		// a group that is covered completely is fulfilled before moving on to the next one
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 5 * payout / 2));
+		assert_eq!(Stablecoin::get_balance(11), 3 * payout);
+		assert_eq!(Stablecoin::get_balance(12), payout);
+		assert_eq!(Stablecoin::get_balance(13), payout / 2);
+		assert_eq!(Stablecoin::bonds_range(), (2, 1));
+		assert!(Stablecoin::bonds_of(&11).is_empty());
+	});
+}
+
+#[test]
+fn pro_rata_payout_hands_out_rounding_leftover_to_the_group() {
+	PayoutMode::set(BondPayoutMode::ProRata);
+	new_test_ext().execute_with(|| {
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(11, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(12, payout, test_bond_price()));
+		let shareholder_balance = Stablecoin::get_balance(1);
+
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 1));
+		assert_eq!(Stablecoin::get_balance(11), 1);
+		assert_eq!(Stablecoin::get_balance(12), 0);
+		assert_eq!(Stablecoin::get_balance(1), shareholder_balance);
+		assert_eq!(Stablecoin::get_bond(0).payout, payout - 1);
+		assert_eq!(Stablecoin::get_bond(1).payout, payout);
+
+		// 3 split among payouts of 999 and 1_000 --> shares of 1 and 1, leftover goes to the first bond
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 3));
+		assert_eq!(Stablecoin::get_balance(11), 3);
+		assert_eq!(Stablecoin::get_balance(12), 1);
+		assert_eq!(Stablecoin::get_balance(1), shareholder_balance);
+	});
+}
+
+#[test]
+fn pro_rata_leftover_can_fulfill_the_first_bonds() {
+	PayoutMode::set(BondPayoutMode::ProRata);
+	new_test_ext().execute_with(|| {
+		add_bond(Stablecoin::new_bond(11, 1, test_bond_price()));
+		add_bond(Stablecoin::new_bond(12, 1, test_bond_price()));
+
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 1));
+		assert_eq!(Stablecoin::get_balance(11), 1);
+		assert_eq!(Stablecoin::get_balance(12), 0);
+		assert!(Stablecoin::bonds_of(&11).is_empty());
+		assert_eq!(Stablecoin::bonds_range(), (1, 1));
+		assert_eq!(Stablecoin::bonds_of(&12), vec![(1, Stablecoin::get_bond(1))]);
+	});
+}
+
+#[test]
+fn seigniorage_statistics() {
+	new_test_ext().execute_with(|| {
+		let payout = BaseUnit::get();
+		assert_eq!(Stablecoin::total_minted_to_shares(), InitialSupply::get());
+		assert_eq!(Stablecoin::total_burned(), 0);
+		assert_eq!(Stablecoin::total_bond_payouts(), 0);
+
+		let price = Perbill::from_percent(80);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, payout));
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), price * payout), price * payout);
+		assert_eq!(Stablecoin::total_burned(), price * payout);
+
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 3 * payout));
+		assert_eq!(Stablecoin::total_bond_payouts(), payout);
+		assert_eq!(Stablecoin::total_minted_to_shares(), InitialSupply::get() + 2 * payout);
+	});
+}
+
+#[test]
+fn supply_changed_event() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payout = BaseUnit::get();
+		let supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_supply(supply, payout));
+		assert_eq!(
+			stablecoin_events(),
+			vec![
+				RawEvent::ExpandedSupply(payout),
+				RawEvent::SupplyChanged(supply, supply + payout)
+			]
+		);
+
+		let price = Perbill::from_percent(80);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, payout));
+		assert_ok!(Stablecoin::contract_supply(supply + payout, price * payout), price * payout);
+		assert_eq!(
+			stablecoin_events().last(),
+			Some(&RawEvent::SupplyChanged(supply + payout, supply + payout - price * payout))
+		);
+	});
+}
+
+#[test]
+fn partial_bid_conversion_event() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let quantity = 5 * BaseUnit::get();
+		let price = Perbill::from_percent(50);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+
+		// contract by the price of a fifth of the bid
+		let amount = price * BaseUnit::get();
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), amount), amount);
+
+		assert!(stablecoin_events().contains(&RawEvent::BidPartiallyConverted(
+			1,
+			BaseUnit::get(),
+			quantity - BaseUnit::get()
+		)));
+		assert_eq!(
+			bid_terms(Stablecoin::bond_bids()),
+			vec![(1, price, quantity - BaseUnit::get())]
+		);
+	});
+}
+
+#[test]
+fn force_expand_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(11, payout, test_bond_price()));
+		let prev_supply = Stablecoin::coin_supply();
+		let shareholder_balance = Stablecoin::get_balance(1);
+
+		assert_noop!(
+			Stablecoin::force_expand(Origin::signed(1), 2 * payout),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Stablecoin::force_expand(system::RawOrigin::Root.into(), 2 * payout));
+
+		// the bond is paid out first and the rest is handed out to the 10 shareholders
+		assert_eq!(Stablecoin::get_balance(11), payout);
+		assert_eq!(Stablecoin::get_balance(1), shareholder_balance + payout / 10);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + 2 * payout);
+		assert!(stablecoin_events().contains(&RawEvent::ExpandedSupply(2 * payout)));
+	});
+}
+
+#[test]
+fn supply_change_hook_test() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let price = Perbill::from_percent(80);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		let prev_supply = Stablecoin::coin_supply();
+
+		assert_ok!(Stablecoin::contract_supply(prev_supply, price * quantity), price * quantity);
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 3 * quantity));
+		// nothing is reported if there are no bids to contract with
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), quantity), 0);
+
+		assert_eq!(
+			supply_changes(),
+			vec![
+				SupplyChange::Contract(price * quantity),
+				SupplyChange::Expand(3 * quantity)
+			]
+		);
+	});
+}
+
+#[test]
+fn force_contract_test() {
+	new_test_ext().execute_with(|| {
+		let quantity = BaseUnit::get();
+		let price = Perbill::from_percent(80);
+		assert_ok!(Stablecoin::bid_for_bond(Origin::signed(1), price, quantity));
+		let prev_supply = Stablecoin::coin_supply();
+
+		assert_noop!(
+			Stablecoin::force_contract(Origin::signed(1), price * quantity),
+			DispatchError::BadOrigin
+		);
+		// cannot contract below the minimum supply
+		assert_noop!(
+			Stablecoin::force_contract(
+				system::RawOrigin::Root.into(),
+				prev_supply - MinimumSupply::get() + 1
+			),
+			Error::<Test>::CoinSupplyUnderflow
+		);
+
+		assert_ok!(Stablecoin::force_contract(system::RawOrigin::Root.into(), price * quantity));
+		assert_eq!(Stablecoin::coin_supply(), prev_supply - price * quantity);
+		assert!(Stablecoin::bond_bids().is_empty());
+		let bonds = Stablecoin::bonds_of(&1);
+		assert_eq!(bonds.len(), 1);
+		assert_eq!(bonds[0].1.payout, quantity);
+	});
+}
+
+#[test]
+fn force_contract_respects_minimum_supply_without_bids() {
+	new_test_ext().execute_with(|| {
+		assert!(Stablecoin::bond_bids().is_empty());
+		let supply = Stablecoin::coin_supply();
+
+		assert_noop!(
+			Stablecoin::force_contract(system::RawOrigin::Root.into(), supply),
+			Error::<Test>::CoinSupplyUnderflow
+		);
+		assert_noop!(
+			Stablecoin::force_contract(system::RawOrigin::Root.into(), supply - MinimumSupply::get() + 1),
+			Error::<Test>::CoinSupplyUnderflow
+		);
+		// contracting within the minimum supply only reports the missing bids
+		assert_ok!(Stablecoin::force_contract(system::RawOrigin::Root.into(), BaseUnit::get()));
+		assert_eq!(Stablecoin::coin_supply(), supply);
+	});
+}
+
+#[test]
+fn expire_bonds_and_expand_supply() {
+	new_test_ext_with(vec![1]).execute_with(|| {
+		let first_acc = 3;
+		let prev_first_acc_balance = Stablecoin::get_balance(first_acc);
+		// 1.2 * BaseUnit
+		let payout = Fixed64::from_rational(20, 100).saturated_multiply_accumulate(BaseUnit::get());
+		add_bond(Stablecoin::new_bond(first_acc, payout, test_bond_price()));
+
+		let (start, length) = Stablecoin::bonds_range();
+		assert_eq!(length, 1);
+		let bond = &Stablecoin::get_bond(start);
+		assert_eq!(bond.expiration, System::block_number() + ExpirationPeriod::get());
+
+		let prev_supply = Stablecoin::coin_supply();
+		let second_acc = first_acc + 1;
+		let prev_second_acc_balance = Stablecoin::get_balance(second_acc);
 		// set blocknumber to the block number right before the first bond's expiration block
 		System::set_block_number(System::block_number() + ExpirationPeriod::get() - 1);
 		// Add a new bond
-		add_bond(Stablecoin::new_bond(second_acc, payout));
-		add_bond(Stablecoin::new_bond(second_acc, payout));
-		add_bond(Stablecoin::new_bond(second_acc, payout));
+		add_bond(Stablecoin::new_bond(second_acc, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(second_acc, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(second_acc, payout, test_bond_price()));
 		// Note: this one is from first_acc
-		add_bond(Stablecoin::new_bond(first_acc, payout));
+		add_bond(Stablecoin::new_bond(first_acc, payout, test_bond_price()));
 
 		// check bonds length
 		let (_, length) = Stablecoin::bonds_range();
-		// computing the length this way is fine because there was no overflow
 		assert_eq!(length, 5);
 		// Increase block number by one so that we reach the first bond's expiration block number.
 		System::set_block_number(System::block_number() + 1);
@@ -382,7 +2723,6 @@ fn expire_bonds_and_expand_supply() {
 		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), new_coins));
 		// make sure there are only three bonds left (the first one expired, the second one got consumed)
 		let (_, length) = Stablecoin::bonds_range();
-		// computing the length this way is fine because there was no overflow
 		assert_eq!(length, 3);
 		// make sure the first account's balance hasn't changed
 		assert_eq!(prev_first_acc_balance, Stablecoin::get_balance(first_acc));
@@ -395,107 +2735,508 @@ fn expire_bonds_and_expand_supply() {
 		// make sure total supply increased by `new_coins`
 		assert_eq!(prev_supply + new_coins, Stablecoin::coin_supply());
 
-		let intermediate_supply = Stablecoin::coin_supply();
-		// Set the block number to be *exactly equal* to the expiration date of all bonds that are left in the queue
-		System::set_block_number(System::block_number() + ExpirationPeriod::get() - 1);
+		let intermediate_supply = Stablecoin::coin_supply();
+		// Set the block number to be *exactly equal* to the expiration date of all bonds that are left in the queue
+		System::set_block_number(System::block_number() + ExpirationPeriod::get() - 1);
+
+		// try to expand_supply, expected to do nothing because all bonds have expired
+		let new_coins = 42;
+		assert_ok!(Stablecoin::expand_supply(intermediate_supply, new_coins));
+
+		// make sure there are no bonds left (they have all expired)
+		let (_, length) = Stablecoin::bonds_range();
+		assert_eq!(length, 0);
+
+		// make sure first and second's balances haven't changed
+		assert_eq!(prev_first_acc_balance, Stablecoin::get_balance(first_acc));
+		assert_eq!(
+			intermediate_second_acc_balance,
+			Stablecoin::get_balance(second_acc)
+		);
+
+		// Make sure coin supply has increased by `new_coins`
+		assert_eq!(
+			intermediate_supply + new_coins,
+			Stablecoin::coin_supply(),
+			"coin supply should not change as the bond expired"
+		);
+	});
+}
+
+// ------------------------------------------------------------
+// handout tests
+
+#[test]
+fn simple_handout_test() {
+	new_test_ext().execute_with(|| {
+		let balance_per_acc = InitialSupply::get() / 10;
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc);
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+
+		let amount = 30 * BaseUnit::get();
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			amount,
+			Stablecoin::coin_supply()
+		));
+
+		let amount_per_acc = 3 * BaseUnit::get();
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(2), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(3), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(7), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc + amount_per_acc);
+	});
+}
+
+#[test]
+fn handout_less_than_shares_test() {
+	new_test_ext().execute_with(|| {
+		let balance_per_acc = InitialSupply::get() / 10;
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc);
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+
+		let amount = 8;
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			amount,
+			Stablecoin::coin_supply()
+		));
+
+		let amount_per_acc = 1;
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(2), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(3), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(7), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(8), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(9), balance_per_acc);
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+	});
+}
+
+#[test]
+fn handout_more_than_shares_test() {
+	new_test_ext().execute_with(|| {
+		let balance_per_acc = InitialSupply::get() / 10;
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc);
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+
+		let amount = 13;
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			amount,
+			Stablecoin::coin_supply()
+		));
+
+		let amount_per_acc = 1;
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + amount_per_acc + 1);
+		assert_eq!(Stablecoin::get_balance(2), balance_per_acc + amount_per_acc + 1);
+		assert_eq!(Stablecoin::get_balance(3), balance_per_acc + amount_per_acc + 1);
+		assert_eq!(Stablecoin::get_balance(4), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(8), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc + amount_per_acc);
+	});
+}
+
+#[test]
+fn handout_rotates_extra_coins() {
+	new_test_ext().execute_with(|| {
+		let balance_per_acc = InitialSupply::get() / 10;
+
+		let amount = 13;
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			amount,
+			Stablecoin::coin_supply()
+		));
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			amount,
+			Stablecoin::coin_supply()
+		));
+
+		// accounts 1 to 3 get the extra coin in the first handout, 4 to 6 in the second
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + 2 + 1);
+		assert_eq!(Stablecoin::get_balance(3), balance_per_acc + 2 + 1);
+		assert_eq!(Stablecoin::get_balance(4), balance_per_acc + 1 + 2);
+		assert_eq!(Stablecoin::get_balance(6), balance_per_acc + 1 + 2);
+		assert_eq!(Stablecoin::get_balance(7), balance_per_acc + 1 + 1);
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc + 1 + 1);
+		assert_eq!(Stablecoin::handout_cursor(), 6);
+		assert_eq!(Stablecoin::coin_supply(), InitialSupply::get() + 2 * amount);
+	});
+}
+
+#[test]
+fn handout_extra_coins_even_out() {
+	new_test_ext().execute_with(|| {
+		let balance_per_acc = InitialSupply::get() / 10;
+		// 3 extra coins per handout --> 30 extra coins over 10 handouts, 3 for every account
+		for _ in 0..10 {
+			assert_ok!(Stablecoin::hand_out_coins(
+				&Stablecoin::shares(),
+				13,
+				Stablecoin::coin_supply()
+			));
+		}
+		for acc in 1..=10 {
+			// 1 coin per share in each handout plus 3 extra coins
+			assert_eq!(Stablecoin::get_balance(acc), balance_per_acc + 10 + 3);
+		}
+		assert_eq!(Stablecoin::handout_cursor(), 0);
+	});
+}
+
+#[test]
+fn handout_mints_initial_supply_with_weighted_shares() {
+	new_test_ext_with_shares(vec![(1, 5), (2, 1)]).execute_with(|| {
+		// 100_000 * 5 / 6 = 83_333 and 100_000 / 6 = 16_666 with 1 Coin left over by rounding
+		// which goes to the first shareholder
+		assert_eq!(Stablecoin::get_balance(1), 83_333 + 1);
+		assert_eq!(Stablecoin::get_balance(2), 16_666);
+		assert_eq!(Stablecoin::coin_supply(), InitialSupply::get());
+
+		// 10 * 5 / 6 = 8 and 10 / 6 = 1 with the extra coin going to the second shareholder
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			10,
+			Stablecoin::coin_supply()
+		));
+		assert_eq!(Stablecoin::get_balance(1), 83_333 + 1 + 8);
+		assert_eq!(Stablecoin::get_balance(2), 16_666 + 1 + 1);
+		assert_eq!(Stablecoin::coin_supply(), InitialSupply::get() + 10);
+	});
+}
+
+#[test]
+fn handout_with_fewer_coins_than_shares() {
+	new_test_ext_with_shares(vec![(1, 100)]).execute_with(|| {
+		let balance = InitialSupply::get();
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			50,
+			Stablecoin::coin_supply()
+		));
+		assert_eq!(Stablecoin::get_balance(1), balance + 50);
+		assert_eq!(Stablecoin::coin_supply(), balance + 50);
+	});
+
+	new_test_ext_with_shares(vec![(1, 1), (2, 99)]).execute_with(|| {
+		assert_eq!(Stablecoin::get_balance(1), InitialSupply::get() / 100);
+		assert_eq!(Stablecoin::get_balance(2), InitialSupply::get() / 100 * 99);
+
+		// 50 / 100 = 0 and 50 * 99 / 100 = 49 with the extra coin going to the first shareholder
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			50,
+			Stablecoin::coin_supply()
+		));
+		assert_eq!(Stablecoin::get_balance(1), InitialSupply::get() / 100 + 1);
+		assert_eq!(Stablecoin::get_balance(2), InitialSupply::get() / 100 * 99 + 49);
+
+		// the extra coin goes to the second shareholder this time
+		assert_ok!(Stablecoin::hand_out_coins(
+			&Stablecoin::shares(),
+			50,
+			Stablecoin::coin_supply()
+		));
+		assert_eq!(Stablecoin::get_balance(1), InitialSupply::get() / 100 + 1);
+		assert_eq!(Stablecoin::get_balance(2), InitialSupply::get() / 100 * 99 + 49 + 50);
+		assert_eq!(Stablecoin::coin_supply(), InitialSupply::get() + 100);
+	});
+}
+
+#[test]
+fn handout_without_shares_fails() {
+	new_test_ext().execute_with(|| {
+		let supply = Stablecoin::coin_supply();
+		assert_noop!(
+			Stablecoin::hand_out_coins(&[], 10, supply),
+			Error::<Test>::NoShares
+		);
+		assert_noop!(
+			Stablecoin::hand_out_coins(&[(1, 0), (2, 0)], 10, supply),
+			Error::<Test>::NoShares
+		);
+	});
+}
+
+#[test]
+fn handout_share_supply_overflow_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Stablecoin::hand_out_coins(&[(1, u64::max_value()), (2, 1)], 10, Stablecoin::coin_supply()),
+			Error::<Test>::GenericOverflow
+		);
+	});
+}
+
+#[test]
+fn expand_supply_without_shares_only_pays_bonds() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		<Shareholders<Test>>::kill();
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(11, payout, test_bond_price()));
+		let prev_supply = Stablecoin::coin_supply();
+
+		assert_ok!(Stablecoin::expand_supply(prev_supply, 3 * payout));
+
+		assert_eq!(Stablecoin::get_balance(11), payout);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + payout);
+		assert!(stablecoin_events().contains(&RawEvent::ExpandedSupply(payout)));
+	});
+}
+
+#[test]
+fn expand_supply_pays_bonds_and_hands_out_the_rest() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(11, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(12, payout, test_bond_price()));
+		let prev_supply = Stablecoin::coin_supply();
+		let balance_per_acc = InitialSupply::get() / 10;
+
+		let amount = 2 * payout + 10 * BaseUnit::get();
+		assert_ok!(Stablecoin::expand_supply(prev_supply, amount));
 
-		// try to expand_supply, expected to do nothing because all bonds have expired
-		let new_coins = 42;
-		assert_ok!(Stablecoin::expand_supply(intermediate_supply, new_coins));
+		assert_eq!(Stablecoin::get_balance(11), payout);
+		assert_eq!(Stablecoin::get_balance(12), payout);
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + BaseUnit::get());
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc + BaseUnit::get());
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + amount);
+		assert_eq!(Stablecoin::total_bond_payouts(), 2 * payout);
+		assert!(stablecoin_events().contains(&RawEvent::ExpandedSupply(amount)));
+	});
+}
 
-		// make sure there are no bonds left (they have all expired)
-		let (_, length) = Stablecoin::bonds_range();
-		// computing the length this way is fine because there was no overflow
-		assert_eq!(length, 0);
+#[test]
+fn bond_fulfilled_events_track_the_remaining_expansion() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payout = BaseUnit::get();
+		for (acc, multiple) in [(11, 1), (12, 2), (13, 3)].iter() {
+			add_bond(Stablecoin::new_bond(*acc, multiple * payout, test_bond_price()));
+		}
 
-		// make sure first and second's balances haven't changed
-		assert_eq!(prev_first_acc_balance, Stablecoin::get_balance(first_acc));
-		assert_eq!(
-			intermediate_second_acc_balance,
-			Stablecoin::get_balance(second_acc)
-		);
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 10 * payout));
 
-		// Make sure coin supply has increased by `new_coins`
+		let fulfilled: Vec<_> = stablecoin_events()
+			.into_iter()
+			.filter(|e| matches!(e, RawEvent::BondFulfilled(..)))
+			.collect();
 		assert_eq!(
-			intermediate_supply + new_coins,
-			Stablecoin::coin_supply(),
-			"coin supply should not change as the bond expired"
+			fulfilled,
+			vec![
+				RawEvent::BondFulfilled(11, payout, 9 * payout),
+				RawEvent::BondFulfilled(12, 2 * payout, 7 * payout),
+				RawEvent::BondFulfilled(13, 3 * payout, 4 * payout),
+			]
 		);
 	});
 }
 
-// ------------------------------------------------------------
-// handout tests
+#[test]
+fn capped_bond_is_paid_out_across_expansions() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		MaxBondPayoutPerExpansion::set(2 * BaseUnit::get());
+		let big = 5 * BaseUnit::get();
+		let small = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(11, big, test_bond_price()));
+		add_bond(Stablecoin::new_bond(12, small, test_bond_price()));
+		let prev_supply = Stablecoin::coin_supply();
+		let shareholder_balance = Stablecoin::get_balance(1);
+
+		// the big bond is capped and the small bond behind it is fulfilled
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 4 * BaseUnit::get()));
+		assert_eq!(Stablecoin::get_balance(11), 2 * BaseUnit::get());
+		assert_eq!(Stablecoin::get_balance(12), small);
+		assert_eq!(Stablecoin::bond_count(), 1);
+		let (start, _) = Stablecoin::bonds_range();
+		assert_eq!(Stablecoin::get_bond(start).payout, 3 * BaseUnit::get());
+		assert_eq!(Stablecoin::bonds_of(&11), vec![(start, Stablecoin::get_bond(start))]);
+		assert!(Stablecoin::bonds_of(&12).is_empty());
+		// the rest is not handed out to shareholders while the capped bond is outstanding
+		assert_eq!(Stablecoin::get_balance(1), shareholder_balance);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + 3 * BaseUnit::get());
+		assert!(stablecoin_events().contains(&RawEvent::ExpandedSupply(3 * BaseUnit::get())));
+		assert!(stablecoin_events().contains(&RawEvent::BondPartiallyFulfilled(
+			11,
+			2 * BaseUnit::get(),
+			3 * BaseUnit::get()
+		)));
+
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 4 * BaseUnit::get()));
+		assert_eq!(Stablecoin::get_balance(11), 4 * BaseUnit::get());
+		assert_eq!(Stablecoin::get_bond(start).payout, BaseUnit::get());
+
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 4 * BaseUnit::get()));
+		assert_eq!(Stablecoin::get_balance(11), big);
+		assert_eq!(Stablecoin::bond_count(), 0);
+	});
+}
 
 #[test]
-fn simple_handout_test() {
+fn capped_pro_rata_payout() {
+	PayoutMode::set(BondPayoutMode::ProRata);
 	new_test_ext().execute_with(|| {
-		let balance_per_acc = InitialSupply::get() / 10;
-		assert_eq!(Stablecoin::get_balance(1), balance_per_acc);
-		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+		MaxBondPayoutPerExpansion::set(2 * BaseUnit::get());
+		add_bond(Stablecoin::new_bond(11, 5 * BaseUnit::get(), test_bond_price()));
+		add_bond(Stablecoin::new_bond(12, BaseUnit::get(), test_bond_price()));
+		let prev_supply = Stablecoin::coin_supply();
+		let shareholder_balance = Stablecoin::get_balance(1);
 
-		let amount = 30 * BaseUnit::get();
-		assert_ok!(Stablecoin::hand_out_coins(
-			&Stablecoin::shares(),
-			amount,
-			Stablecoin::coin_supply()
-		));
+		// shares of 3_333 and 666 --> the first is capped at 2_000, the second takes 334 more
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 4 * BaseUnit::get()));
+		assert_eq!(Stablecoin::get_balance(11), 2 * BaseUnit::get());
+		assert_eq!(Stablecoin::get_balance(12), BaseUnit::get());
+		assert_eq!(Stablecoin::bond_count(), 1);
+		let (start, _) = Stablecoin::bonds_range();
+		assert_eq!(Stablecoin::get_bond(start).payout, 3 * BaseUnit::get());
+		assert_eq!(Stablecoin::bonds_of(&11), vec![(start, Stablecoin::get_bond(start))]);
+		assert!(Stablecoin::bonds_of(&12).is_empty());
+		assert_eq!(Stablecoin::get_balance(1), shareholder_balance);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + 3 * BaseUnit::get());
+	});
+}
 
-		let amount_per_acc = 3 * BaseUnit::get();
-		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(2), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(3), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(7), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(10), balance_per_acc + amount_per_acc);
+#[test]
+fn capped_bonds_do_not_block_later_bonds() {
+	PayoutMode::set(BondPayoutMode::ProRata);
+	new_test_ext().execute_with(|| {
+		MaxBondPayoutPerExpansion::set(BaseUnit::get());
+		add_bond(Stablecoin::new_bond(11, 2 * BaseUnit::get(), test_bond_price()));
+		System::set_block_number(2);
+		add_bond(Stablecoin::new_bond(12, 2 * BaseUnit::get(), test_bond_price()));
+
+		// the first group is capped, the rest goes to the next group
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), 2 * BaseUnit::get()));
+		assert_eq!(Stablecoin::get_balance(11), BaseUnit::get());
+		assert_eq!(Stablecoin::get_balance(12), BaseUnit::get());
+		assert_eq!(Stablecoin::bond_count(), 2);
+		let (start, _) = Stablecoin::bonds_range();
+		assert_eq!(Stablecoin::bonds_of(&11), vec![(start, Stablecoin::get_bond(start))]);
+		assert_eq!(Stablecoin::bonds_of(&12), vec![(start + 1, Stablecoin::get_bond(start + 1))]);
 	});
 }
 
 #[test]
-fn handout_less_than_shares_test() {
+fn partially_fulfilled_bond_event_reports_the_paid_amount() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payout = 3 * BaseUnit::get();
+		add_bond(Stablecoin::new_bond(11, payout, test_bond_price()));
+
+		let paid = BaseUnit::get();
+		assert_ok!(Stablecoin::expand_supply(Stablecoin::coin_supply(), paid));
+
+		assert_eq!(Stablecoin::get_balance(11), paid);
+		assert_eq!(Stablecoin::get_bond(0).payout, payout - paid);
+		assert!(stablecoin_events().contains(&RawEvent::BondPartiallyFulfilled(11, paid, payout - paid)));
+	});
+}
+
+#[test]
+fn expand_supply_with_failing_handout_only_pays_bonds() {
 	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payout = BaseUnit::get();
+		add_bond(Stablecoin::new_bond(11, payout, test_bond_price()));
+		// makes the share supply of the handout overflow
+		<Shares<Test>>::insert(1, u64::max_value());
+		let prev_supply = Stablecoin::coin_supply();
 		let balance_per_acc = InitialSupply::get() / 10;
+
+		assert_ok!(Stablecoin::expand_supply(prev_supply, 3 * payout));
+
+		assert_eq!(Stablecoin::get_balance(11), payout);
 		assert_eq!(Stablecoin::get_balance(1), balance_per_acc);
-		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + payout);
+		assert!(stablecoin_events().contains(&RawEvent::ExpandedSupply(payout)));
+	});
+}
 
-		let amount = 8;
-		assert_ok!(Stablecoin::hand_out_coins(
-			&Stablecoin::shares(),
-			amount,
-			Stablecoin::coin_supply()
-		));
+#[test]
+fn expanded_supply_hands_out_every_coin() {
+	new_test_ext_with_shares(vec![(1, 3), (2, 5), (3, 1)]).execute_with(|| {
+		System::set_block_number(1);
+		// 17 Coins are not divisible by 9 shares, but nothing is carried over
+		let prev_supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_supply(prev_supply, 17));
+		assert_eq!(Stablecoin::coin_supply(), prev_supply + 17);
+		assert!(stablecoin_events().contains(&RawEvent::ExpandedSupply(17)));
 
-		let amount_per_acc = 1;
-		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(2), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(3), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(7), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(8), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(9), balance_per_acc);
-		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+		assert_eq!(supply_changes(), vec![SupplyChange::Expand(17)]);
 	});
 }
 
 #[test]
-fn handout_more_than_shares_test() {
+fn projected_shareholder_payout_matches_handout() {
+	new_test_ext_with_shares(vec![(1, 3), (2, 5), (3, 1)]).execute_with(|| {
+		// the first handout moves the cursor, which the second has to account for
+		for amount in [17, 23].iter() {
+			let projected = Stablecoin::projected_shareholder_payout(*amount);
+			let cursor = Stablecoin::handout_cursor();
+			assert_eq!(Stablecoin::handout_cursor(), cursor, "projection should not change state");
+			let balances_before: Vec<Coins> = (1..=3).map(Stablecoin::get_balance).collect();
+
+			assert_ok!(Stablecoin::hand_out_coins(
+				&Stablecoin::shares(),
+				*amount,
+				Stablecoin::coin_supply()
+			));
+
+			let received: Vec<(AccountId, Coins)> = (1..=3)
+				.zip(balances_before)
+				.map(|(acc, before)| (acc, Stablecoin::get_balance(acc) - before))
+				.collect();
+			assert_eq!(projected, received);
+		}
+	});
+}
+
+#[test]
+fn projected_shareholder_payout_without_shares_is_empty() {
+	new_test_ext().execute_with(|| {
+		<Shareholders<Test>>::kill();
+		assert_eq!(Stablecoin::projected_shareholder_payout(10), vec![]);
+	});
+}
+
+#[test]
+fn minted_shares_dilute_future_handouts() {
 	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
 		let balance_per_acc = InitialSupply::get() / 10;
+		assert_noop!(
+			Stablecoin::mint_shares(Origin::signed(1), 11, 10),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stablecoin::mint_shares(system::RawOrigin::Root.into(), 11, 0),
+			Error::<Test>::ZeroAmount
+		);
+
+		assert_ok!(Stablecoin::mint_shares(system::RawOrigin::Root.into(), 11, 10));
+		assert_eq!(Stablecoin::shares_of(11), 10);
+		assert_eq!(Stablecoin::share_supply(), 20);
+		assert_eq!(Stablecoin::shareholders().last(), Some(&11));
+		assert_eq!(stablecoin_events(), vec![RawEvent::SharesMinted(11, 10)]);
+		// existing balances are unaffected
 		assert_eq!(Stablecoin::get_balance(1), balance_per_acc);
-		assert_eq!(Stablecoin::get_balance(10), balance_per_acc);
+		assert_eq!(Stablecoin::get_balance(11), 0);
 
-		let amount = 13;
+		let amount = 20 * BaseUnit::get();
 		assert_ok!(Stablecoin::hand_out_coins(
 			&Stablecoin::shares(),
 			amount,
 			Stablecoin::coin_supply()
 		));
-
-		let amount_per_acc = 1;
-		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + amount_per_acc + 1);
-		assert_eq!(Stablecoin::get_balance(2), balance_per_acc + amount_per_acc + 1);
-		assert_eq!(Stablecoin::get_balance(3), balance_per_acc + amount_per_acc + 1);
-		assert_eq!(Stablecoin::get_balance(4), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(8), balance_per_acc + amount_per_acc);
-		assert_eq!(Stablecoin::get_balance(10), balance_per_acc + amount_per_acc);
+		assert_eq!(Stablecoin::get_balance(1), balance_per_acc + BaseUnit::get());
+		assert_eq!(Stablecoin::get_balance(10), balance_per_acc + BaseUnit::get());
+		assert_eq!(Stablecoin::get_balance(11), 10 * BaseUnit::get());
 	});
 }
 
@@ -521,7 +3262,7 @@ fn handout_quickcheck() {
 
 		let first = shareholders[0];
 
-		new_test_ext_with(shareholders).execute_with(|| {
+		new_test_ext_with(shareholders.clone()).execute_with(|| {
 			let amount = amount;
 			// this assert might actually produce a false positive
 			// as there might be errors returned that are the correct
@@ -538,6 +3279,19 @@ fn handout_quickcheck() {
 			assert_ge!(balance, InitialSupply::get() / len + payout / len);
 			assert_le!(balance, InitialSupply::get() / len + 1 + payout / len + 1);
 
+			// over many handouts the rounding should even out
+			for _ in 0..10 {
+				assert_ok!(Stablecoin::hand_out_coins(
+					&Stablecoin::shares(),
+					amount,
+					Stablecoin::coin_supply()
+				));
+			}
+			let balances: Vec<Coins> = shareholders.iter().map(|acc| Stablecoin::get_balance(acc)).collect();
+			let max_balance = balances.iter().max().expect("there is at least one shareholder");
+			let min_balance = balances.iter().min().expect("there is at least one shareholder");
+			assert_le!(max_balance - min_balance, 1);
+
 			TestResult::passed()
 		})
 	}
@@ -549,6 +3303,141 @@ fn handout_quickcheck() {
 		.quickcheck(property as fn(Vec<u64>, u64) -> TestResult)
 }
 
+// ------------------------------------------------------------
+// oracle adapters
+#[test]
+fn clamped_price_limits_spikes() {
+	new_test_ext().execute_with(|| {
+		type Clamped = ClampedPrice<TestPrice, MaxPriceDelta>;
+		OraclePrice::set(TEST_BASE_UNIT);
+		assert_eq!(Clamped::fetch_price(), TEST_BASE_UNIT);
+
+		// 10x spike is limited to 10% per fetch
+		OraclePrice::set(10 * TEST_BASE_UNIT);
+		assert_eq!(Clamped::fetch_price(), 1_100);
+		assert_eq!(Clamped::fetch_price(), 1_210);
+
+		// crash is limited as well
+		OraclePrice::set(TEST_BASE_UNIT / 10);
+		assert_eq!(Clamped::fetch_price(), 1_089);
+
+		// price changes within the bounds are passed through
+		OraclePrice::set(1_000);
+		assert_eq!(Clamped::fetch_price(), 1_000);
+	});
+}
+
+/// Gives the same weight to three feeds.
+pub struct EqualWeights;
+
+impl Get<Vec<u32>> for EqualWeights {
+	fn get() -> Vec<u32> {
+		vec![1, 1, 1]
+	}
+}
+
+/// Gives the first of three feeds the majority of the weight.
+pub struct FirstFeedMajority;
+
+impl Get<Vec<u32>> for FirstFeedMajority {
+	fn get() -> Vec<u32> {
+		vec![3, 1, 1]
+	}
+}
+
+#[test]
+fn composite_price_ignores_outlier() {
+	new_test_ext().execute_with(|| {
+		type Feeds = (TestPrice, FixedPrice<FeedPriceA>, FixedPrice<FeedPriceB>);
+		type Composite = CompositePrice<Feeds, EqualWeights>;
+
+		OraclePrice::set(1_005);
+		assert_eq!(Composite::fetch_price(), 1_005);
+
+		// a single manipulated feed does not move the median
+		OraclePrice::set(10 * TEST_BASE_UNIT);
+		assert_eq!(Composite::fetch_price(), FeedPriceB::get());
+		OraclePrice::set(1);
+		assert_eq!(Composite::fetch_price(), FeedPriceA::get());
+
+		// a failing feed is excluded instead of poisoning the result
+		OraclePrice::set(0);
+		assert_eq!(Composite::fetch_price(), FeedPriceA::get());
+
+		// the weights decide the median
+		OraclePrice::set(10 * TEST_BASE_UNIT);
+		assert_eq!(
+			CompositePrice::<Feeds, FirstFeedMajority>::fetch_price(),
+			10 * TEST_BASE_UNIT
+		);
+	});
+}
+
+#[test]
+fn clamped_price_moves_from_small_prices() {
+	new_test_ext().execute_with(|| {
+		type Clamped = ClampedPrice<TestPrice, MaxPriceDelta>;
+		OraclePrice::set(4);
+		assert_eq!(Clamped::fetch_price(), 4);
+
+		// 10% of 4 rounds to 0 but the price still moves by 1 per fetch
+		OraclePrice::set(TEST_BASE_UNIT);
+		assert_eq!(Clamped::fetch_price(), 5);
+		assert_eq!(Clamped::fetch_price(), 6);
+
+		OraclePrice::set(1);
+		assert_eq!(Clamped::fetch_price(), 5);
+	});
+}
+
+#[test]
+fn moving_average_price_smoothes_outliers() {
+	new_test_ext().execute_with(|| {
+		type Averaged = MovingAveragePrice<TestPrice, PriceWindow>;
+		OraclePrice::set(TEST_BASE_UNIT);
+		for _ in 0..3 {
+			assert_eq!(Averaged::fetch_price(), TEST_BASE_UNIT);
+		}
+
+		// the outlier is spread over the whole window
+		OraclePrice::set(5 * TEST_BASE_UNIT);
+		assert_eq!(Averaged::fetch_price(), 2 * TEST_BASE_UNIT);
+		OraclePrice::set(TEST_BASE_UNIT);
+		for _ in 0..3 {
+			assert_eq!(Averaged::fetch_price(), 2 * TEST_BASE_UNIT);
+		}
+
+		// and drops out after `PriceWindow` fetches
+		assert_eq!(Averaged::fetch_price(), TEST_BASE_UNIT);
+	});
+}
+
+#[test]
+fn oracle_adapters_pass_through_stale_prices() {
+	new_test_ext().execute_with(|| {
+		type Clamped = ClampedPrice<TestPrice, MaxPriceDelta>;
+		type Averaged = MovingAveragePrice<TestPrice, PriceWindow>;
+		type Feeds = (TestPrice, FixedPrice<FeedPriceA>, FixedPrice<FeedPriceB>);
+		type Composite = CompositePrice<Feeds, EqualWeights>;
+		type Single = CompositePrice<(TestPrice,), EqualWeights>;
+		assert_eq!(Clamped::fetch_price_checked(), Some(TEST_BASE_UNIT));
+		assert_eq!(Averaged::fetch_price_checked(), Some(TEST_BASE_UNIT));
+
+		OraclePrice::set(5 * TEST_BASE_UNIT);
+		OracleStale::set(true);
+		assert_eq!(Clamped::fetch_price_checked(), None);
+		assert_eq!(Averaged::fetch_price_checked(), None);
+		assert_eq!(Single::fetch_price_checked(), None);
+		// the stale feed is excluded from the median
+		assert_eq!(Composite::fetch_price_checked(), Some(FeedPriceA::get()));
+
+		// stale prices are not recorded
+		OracleStale::set(false);
+		assert_eq!(Clamped::fetch_price_checked(), Some(1_100));
+		assert_eq!(Averaged::fetch_price_checked(), Some(3 * TEST_BASE_UNIT));
+	});
+}
+
 // ------------------------------------------------------------
 // expand and contract tests
 #[test]
@@ -556,10 +3445,10 @@ fn expand_supply_test() {
 	new_test_ext().execute_with(|| {
 		// payout of 120% of BaseUnit
 		let payout = Fixed64::from_rational(20, 100).saturated_multiply_accumulate(BaseUnit::get());
-		add_bond(Stablecoin::new_bond(2, payout));
-		add_bond(Stablecoin::new_bond(3, payout));
-		add_bond(Stablecoin::new_bond(4, payout));
-		add_bond(Stablecoin::new_bond(5, 7 * payout));
+		add_bond(Stablecoin::new_bond(2, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(3, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(4, payout, test_bond_price()));
+		add_bond(Stablecoin::new_bond(5, 7 * payout, test_bond_price()));
 
 		let prev_supply = Stablecoin::coin_supply();
 		let amount = 13 * BaseUnit::get();
@@ -579,45 +3468,190 @@ fn expand_supply_test() {
 			prev_supply + amount,
 			"supply should be increased by amount"
 		);
-	});
+	});
+}
+
+#[test]
+fn state_snapshot_matches_getters() {
+	new_test_ext().execute_with(|| {
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(80), 2 * TEST_BASE_UNIT));
+		Stablecoin::add_bid(Bid::new(2, Perbill::from_percent(50), 2 * TEST_BASE_UNIT));
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), TEST_BASE_UNIT));
+		assert_ok!(Stablecoin::set_minimum_bond_price(
+			system::RawOrigin::Root.into(),
+			Perbill::from_percent(20)
+		));
+
+		assert_eq!(
+			Stablecoin::state_snapshot(),
+			StablecoinState {
+				coin_supply: Stablecoin::coin_supply(),
+				minimum_supply: MinimumSupply::get(),
+				bid_count: Stablecoin::bond_bids().len() as u64,
+				bond_count: Stablecoin::bond_count(),
+				minimum_bond_price: Stablecoin::minimum_bond_price(),
+				has_shares: true,
+			}
+		);
+		let snapshot = Stablecoin::state_snapshot();
+		assert_eq!(snapshot.coin_supply, InitialSupply::get() - TEST_BASE_UNIT);
+		assert_eq!(snapshot.bid_count, 2);
+		assert_eq!(snapshot.bond_count, 1);
+		assert_eq!(snapshot.minimum_bond_price, Perbill::from_percent(20));
+	});
+}
+
+#[test]
+fn contract_supply_test() {
+	new_test_ext().execute_with(|| {
+		let bond_amount = Ratio::new(125, 100)
+			.checked_mul(&BaseUnit::get().into())
+			.map(|r| r.to_integer())
+			.expect("bond_amount should not have overflowed");
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(80), bond_amount));
+		Stablecoin::add_bid(Bid::new(2, Perbill::from_percent(75), 2 * BaseUnit::get()));
+
+		let prev_supply = Stablecoin::coin_supply();
+		let amount = 2 * BaseUnit::get();
+		assert_eq!(Stablecoin::contract_supply(prev_supply, amount), Ok(amount));
+
+		let bids = Stablecoin::bond_bids();
+		assert_eq!(bids.len(), 1, "exactly one bid should have been removed");
+		let remainging_bid_quantity = Fixed64::from_rational(667, 1_000)
+			.saturated_multiply_accumulate(BaseUnit::get())
+			- BaseUnit::get();
+		assert_eq!(
+			bid_terms(bids),
+			vec![(2, Perbill::from_percent(75), remainging_bid_quantity)]
+		);
+
+		let (start, _) = Stablecoin::bonds_range();
+		assert_eq!(Stablecoin::get_bond(start).payout, bond_amount);
+		assert_eq!(Stablecoin::get_bond(start).price, Perbill::from_percent(80));
+		assert_eq!(
+			Stablecoin::get_bond(start.wrapping_add(1)).payout,
+			Fixed64::from_rational(333, 1_000).saturated_multiply_accumulate(BaseUnit::get())
+		);
+		assert_eq!(
+			Stablecoin::get_bond(start.wrapping_add(1)).price,
+			Perbill::from_percent(75),
+			"bonds should record the price of the bid they originate from"
+		);
+
+		assert_eq!(
+			Stablecoin::coin_supply(),
+			prev_supply - amount,
+			"supply should be decreased by amount"
+		);
+	})
+}
+
+#[test]
+fn contract_supply_returns_contracted_amount() {
+	new_test_ext().execute_with(|| {
+		// payment of exactly one BaseUnit
+		let quantity = 2 * BaseUnit::get();
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), quantity));
+
+		let prev_supply = Stablecoin::coin_supply();
+		let amount = 2 * BaseUnit::get();
+		assert_eq!(
+			Stablecoin::contract_supply(prev_supply, amount),
+			Ok(BaseUnit::get()),
+			"supply should only be contracted by the payment of the single bid"
+		);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply - BaseUnit::get());
+		assert!(Stablecoin::bond_bids().is_empty());
+	})
+}
+
+#[test]
+fn contraction_converts_bid_with_rounded_up_payment_entirely() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		// 15% * 1004 = 150.6 --> payment is rounded up to 151
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(15), 1004));
+
+		let prev_supply = Stablecoin::coin_supply();
+		assert_eq!(Stablecoin::contract_supply(prev_supply, 151), Ok(151));
+
+		assert!(Stablecoin::bond_bids().is_empty());
+		let (start, _) = Stablecoin::bonds_range();
+		assert_eq!(Stablecoin::get_bond(start).payout, 1004);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply - 151);
+		assert!(!stablecoin_events()
+			.iter()
+			.any(|e| matches!(e, RawEvent::RefundedBid(..))));
+	})
+}
+
+#[test]
+fn contraction_summarizes_new_bonds() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		for acc in 1..=10 {
+			assert_ok!(Stablecoin::bid_for_bond(
+				Origin::signed(acc),
+				Perbill::from_percent(50),
+				acc * BaseUnit::get()
+			));
+		}
+		let amount = 20 * BaseUnit::get();
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), amount), amount);
+
+		let events = stablecoin_events();
+		let new_bonds: Vec<(u64, BlockNumber)> = events
+			.iter()
+			.filter_map(|e| match e {
+				RawEvent::NewBond(_acc, payout, expiration) => Some((*payout, *expiration)),
+				_ => None,
+			})
+			.collect();
+		// equal bids are converted in order: the bids of accounts 1 to 8 and part of the bid of account 9
+		assert_eq!(new_bonds.len(), 9);
+		let total_payout: u64 = new_bonds.iter().map(|(payout, _exp)| payout).sum();
+		assert_eq!(total_payout, 2 * amount);
+		let expiration = new_bonds.last().expect("bonds were created").1;
+		assert_eq!(
+			events
+				.iter()
+				.filter(|e| matches!(e, RawEvent::BondsCreated(..)))
+				.collect::<Vec<_>>(),
+			vec![&RawEvent::BondsCreated(9, total_payout, expiration)]
+		);
+	})
 }
 
 #[test]
-fn contract_supply_test() {
+fn contraction_shortfall_is_reported() {
 	new_test_ext().execute_with(|| {
-		let bond_amount = Ratio::new(125, 100)
-			.checked_mul(&BaseUnit::get().into())
-			.map(|r| r.to_integer())
-			.expect("bond_amount should not have overflowed");
-		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(80), bond_amount));
-		Stablecoin::add_bid(Bid::new(2, Perbill::from_percent(75), 2 * BaseUnit::get()));
+		System::set_block_number(1);
+		// payment of exactly one BaseUnit
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), 2 * BaseUnit::get()));
 
-		let prev_supply = Stablecoin::coin_supply();
 		let amount = 2 * BaseUnit::get();
-		assert_ok!(Stablecoin::contract_supply(prev_supply, amount));
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), amount), BaseUnit::get());
 
-		let bids = Stablecoin::bond_bids();
-		assert_eq!(bids.len(), 1, "exactly one bid should have been removed");
-		let remainging_bid_quantity = Fixed64::from_rational(667, 1_000)
-			.saturated_multiply_accumulate(BaseUnit::get())
-			- BaseUnit::get();
-		assert_eq!(
-			bids[0],
-			Bid::new(2, Perbill::from_percent(75), remainging_bid_quantity)
-		);
+		let events = stablecoin_events();
+		assert!(events.contains(&RawEvent::ContractedSupply(BaseUnit::get())));
+		assert!(events.contains(&RawEvent::ContractionShortfall(amount, BaseUnit::get())));
+	})
+}
 
-		let (start, _) = Stablecoin::bonds_range();
-		assert_eq!(Stablecoin::get_bond(start).payout, bond_amount);
-		assert_eq!(
-			Stablecoin::get_bond(start + 1).payout,
-			Fixed64::from_rational(333, 1_000).saturated_multiply_accumulate(BaseUnit::get())
-		);
+#[test]
+fn no_contraction_shortfall_with_enough_bids() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Stablecoin::add_bid(Bid::new(1, Perbill::from_percent(50), 4 * BaseUnit::get()));
 
-		assert_eq!(
-			Stablecoin::coin_supply(),
-			prev_supply - amount,
-			"supply should be decreased by amount"
-		);
+		let amount = 2 * BaseUnit::get();
+		assert_ok!(Stablecoin::contract_supply(Stablecoin::coin_supply(), amount), amount);
+
+		let events = stablecoin_events();
+		assert!(events.contains(&RawEvent::ContractedSupply(amount)));
+		assert!(!events
+			.iter()
+			.any(|e| matches!(e, RawEvent::ContractionShortfall(..))));
 	})
 }
 
@@ -631,7 +3665,7 @@ fn expand_or_contract_quickcheck() {
 
 			for (account, payout) in bonds {
 				if account > 0 && payout > 0 {
-					add_bond(Stablecoin::new_bond(account, payout));
+					add_bond(Stablecoin::new_bond(account, payout, test_bond_price()));
 				}
 			}
 
@@ -661,6 +3695,66 @@ fn expand_or_contract_quickcheck() {
 		.quickcheck(property as fn(Vec<(u64, u64)>, Vec<u64>) -> TestResult)
 }
 
+#[test]
+fn contract_supply_without_bids() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let supply = Stablecoin::coin_supply();
+		let amount = BaseUnit::get();
+		assert!(Stablecoin::bond_bids().is_empty());
+
+		assert_ok!(Stablecoin::contract_supply(supply, amount), 0);
+		assert_eq!(Stablecoin::coin_supply(), supply);
+		assert_eq!(stablecoin_events(), vec![RawEvent::ContractionFailedNoBids(amount)]);
+	});
+}
+
+#[test]
+fn supply_never_below_minimum_quickcheck() {
+	static CONTRACTIONS: AtomicU64 = AtomicU64::new(0);
+
+	fn property(bids: Vec<(u64, u32, u64)>, prices: Vec<u64>) -> TestResult {
+		new_test_ext().execute_with(|| {
+			for (account, percent, quantity) in bids {
+				// bids that are invalid for the given parameters are simply rejected
+				let _ = Stablecoin::bid_for_bond(
+					Origin::signed(1 + account % 10),
+					Perbill::from_percent(percent % 101),
+					// every account can afford up to 10 base units
+					(quantity % 10 + 1) * BaseUnit::get(),
+				);
+			}
+
+			for price in prices {
+				// from 0.1 to 2 base units so both expansions and contractions happen
+				let price = (price % 20 + 1) * BaseUnit::get() / 10;
+				let prev_supply = Stablecoin::coin_supply();
+				// errors are fine as long as the supply stays above the minimum
+				let _ = Stablecoin::expand_or_contract_on_price(price);
+				if Stablecoin::coin_supply() < prev_supply {
+					CONTRACTIONS.fetch_add(1, Ordering::SeqCst);
+				}
+				if Stablecoin::coin_supply() < MinimumSupply::get() {
+					return TestResult::error(format!(
+						"supply {} dropped below minimum after adjusting to price {}",
+						Stablecoin::coin_supply(),
+						price
+					));
+				}
+			}
+
+			TestResult::passed()
+		})
+	}
+
+	QuickCheck::new()
+		.min_tests_passed(5)
+		.tests(50)
+		.max_tests(500)
+		.quickcheck(property as fn(Vec<(u64, u32, u64)>, Vec<u64>) -> TestResult);
+	assert_gt!(CONTRACTIONS.load(Ordering::SeqCst), 0, "no contraction was exercised");
+}
+
 #[test]
 fn expand_or_contract_smoketest() {
 	new_test_ext().execute_with(|| {
@@ -671,24 +3765,488 @@ fn expand_or_contract_smoketest() {
 			.collect();
 
 		for (account, payout) in bonds {
-			add_bond(Stablecoin::new_bond(account, payout));
+			add_bond(Stablecoin::new_bond(account, payout, test_bond_price()));
 		}
 
 		for _ in 0..150 {
 			let price = RandomPrice::fetch_price();
-			Stablecoin::on_block_with_price(0, price).unwrap_or_else(|e| {
+			Stablecoin::on_block_with_price(0, Some(price)).unwrap_or_else(|e| {
 				log::error!("could not adjust supply: {:?}", e);
 			});
 		}
 	})
 }
 
+#[test]
+fn price_observed_on_adjustment_blocks() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let price = BaseUnit::get();
+		let frequency = AdjustmentFrequency::get();
+
+		for block in 1..frequency {
+			assert_ok!(Stablecoin::on_block_with_price(block, Some(price)));
+		}
+		assert!(stablecoin_events().is_empty());
+
+		assert_ok!(Stablecoin::on_block_with_price(frequency, Some(price)));
+		assert_eq!(
+			stablecoin_events(),
+			vec![RawEvent::PriceObserved(frequency, price)]
+		);
+	})
+}
+
+#[test]
+fn returned_to_peg_is_emitted_once_after_adjustment() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let base = BaseUnit::get();
+		let frequency = AdjustmentFrequency::get();
+		let returned = || {
+			stablecoin_events()
+				.into_iter()
+				.filter(|e| matches!(e, RawEvent::ReturnedToPeg(..)))
+				.collect::<Vec<_>>()
+		};
+
+		// at peg from the start --> nothing to return from
+		assert_ok!(Stablecoin::on_block_with_price(frequency, Some(base)));
+		assert!(returned().is_empty());
+
+		assert_ok!(Stablecoin::on_block_with_price(2 * frequency, Some(base * 9 / 10)));
+		assert!(matches!(
+			Stablecoin::last_adjustment_action(),
+			Some(SupplyAction::Expand(_))
+		));
+		assert!(returned().is_empty());
+
+		assert_ok!(Stablecoin::on_block_with_price(3 * frequency, Some(base)));
+		assert_eq!(returned(), vec![RawEvent::ReturnedToPeg(3 * frequency)]);
+
+		assert_ok!(Stablecoin::on_block_with_price(4 * frequency, Some(base)));
+		assert_eq!(returned(), vec![RawEvent::ReturnedToPeg(3 * frequency)]);
+	})
+}
+
+#[test]
+fn set_target_unit_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let base_unit = BaseUnit::get();
+		// falls back to `BaseUnit` without override
+		assert_eq!(Stablecoin::target_unit(), base_unit);
+		assert_noop!(
+			Stablecoin::set_target_unit(Origin::signed(1), 2 * base_unit),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stablecoin::set_target_unit(system::RawOrigin::Root.into(), 0),
+			Error::<Test>::ZeroTargetUnit
+		);
+
+		assert_ok!(Stablecoin::set_target_unit(system::RawOrigin::Root.into(), 2 * base_unit));
+		assert_eq!(Stablecoin::target_unit(), 2 * base_unit);
+		assert_eq!(stablecoin_events(), vec![RawEvent::TargetUnitUpdated(2 * base_unit)]);
+
+		// the new target is at peg and the old one is half of it
+		assert_eq!(Stablecoin::simulate_adjustment(2 * base_unit), Ok(SupplyAction::None));
+		let supply = Stablecoin::coin_supply();
+		assert_eq!(Stablecoin::simulate_adjustment(base_unit), Ok(SupplyAction::Expand(supply)));
+		assert_ok!(Stablecoin::expand_or_contract_on_price(base_unit));
+		assert_eq!(Stablecoin::coin_supply(), 2 * supply);
+	});
+}
+
+#[test]
+fn set_adjustment_frequency_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let price = BaseUnit::get();
+		// falls back to `AdjustmentFrequency` without override
+		assert_eq!(Stablecoin::adjustment_frequency(), AdjustmentFrequency::get());
+		assert_noop!(
+			Stablecoin::set_adjustment_frequency(Origin::signed(1), 5),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stablecoin::set_adjustment_frequency(system::RawOrigin::Root.into(), 0),
+			Error::<Test>::ZeroAdjustmentFrequency
+		);
+		assert_eq!(Stablecoin::adjustment_frequency(), AdjustmentFrequency::get());
+
+		assert_ok!(Stablecoin::set_adjustment_frequency(system::RawOrigin::Root.into(), 5));
+		assert_eq!(Stablecoin::adjustment_frequency(), 5);
+		assert_eq!(stablecoin_events(), vec![RawEvent::AdjustmentFrequencyUpdated(5)]);
+
+		for block in 6..10 {
+			assert_ok!(Stablecoin::on_block_with_price(block, Some(price)));
+		}
+		assert_eq!(stablecoin_events(), vec![RawEvent::AdjustmentFrequencyUpdated(5)]);
+		assert_ok!(Stablecoin::on_block_with_price(10, Some(price)));
+		assert_eq!(
+			stablecoin_events(),
+			vec![
+				RawEvent::AdjustmentFrequencyUpdated(5),
+				RawEvent::PriceObserved(10, price)
+			]
+		);
+	})
+}
+
+#[test]
+fn stale_price_skips_adjustment() {
+	use frame_support::traits::OnInitialize;
+
+	new_test_ext().execute_with(|| {
+		let frequency = AdjustmentFrequency::get();
+		System::set_block_number(frequency);
+		// the price would trigger an expansion if the feed was not stale
+		OraclePrice::set(TEST_BASE_UNIT / 2);
+		OracleStale::set(true);
+		let supply = Stablecoin::coin_supply();
+
+		Stablecoin::on_initialize(frequency);
+		assert_eq!(Stablecoin::coin_supply(), supply);
+		assert_eq!(
+			stablecoin_events(),
+			vec![RawEvent::AdjustmentSkippedStalePrice(frequency)]
+		);
+
+		OracleStale::set(false);
+		Stablecoin::on_initialize(2 * frequency);
+		assert!(Stablecoin::coin_supply() > supply);
+	})
+}
+
+#[test]
+fn paused_pallet_only_allows_configuration() {
+	new_test_ext().execute_with(|| {
+		let root = || -> Origin { system::RawOrigin::Root.into() };
+		assert_ok!(Stablecoin::set_paused(root(), true));
+
+		assert_noop!(Stablecoin::mint_shares(root(), 1, 1), Error::<Test>::Paused);
+		assert_noop!(Stablecoin::force_expand(root(), TEST_BASE_UNIT), Error::<Test>::Paused);
+		assert_noop!(Stablecoin::force_contract(root(), TEST_BASE_UNIT), Error::<Test>::Paused);
+
+		assert_ok!(Stablecoin::set_minimum_bond_price(root(), Perbill::from_percent(20)));
+		assert_ok!(Stablecoin::set_adjustment_frequency(root(), 5));
+		assert_ok!(Stablecoin::set_target_unit(root(), 2 * TEST_BASE_UNIT));
+		assert_ok!(Stablecoin::freeze_account(root(), 2));
+		assert_ok!(Stablecoin::thaw_account(root(), 2));
+		assert_ok!(Stablecoin::set_paused(root(), false));
+
+		assert_ok!(Stablecoin::mint_shares(root(), 1, 1));
+	});
+}
+
+#[test]
+fn paused_pallet_skips_adjustment() {
+	use frame_support::traits::OnInitialize;
+
+	new_test_ext().execute_with(|| {
+		let frequency = AdjustmentFrequency::get();
+		System::set_block_number(frequency);
+		// the price triggers an expansion unless the pallet is paused
+		OraclePrice::set(TEST_BASE_UNIT / 2);
+		let supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::set_paused(system::RawOrigin::Root.into(), true));
+
+		Stablecoin::on_initialize(frequency);
+		assert_eq!(Stablecoin::coin_supply(), supply);
+		assert_eq!(stablecoin_events(), vec![RawEvent::Paused]);
+
+		assert_ok!(Stablecoin::set_paused(system::RawOrigin::Root.into(), false));
+		Stablecoin::on_initialize(2 * frequency);
+		assert!(Stablecoin::coin_supply() > supply);
+	})
+}
+
+#[test]
+fn prices_out_of_bounds_skip_adjustment() {
+	MinPriceBound::set(TEST_BASE_UNIT / 10);
+	MaxPriceBound::set(10 * TEST_BASE_UNIT);
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let frequency = AdjustmentFrequency::get();
+		let supply = Stablecoin::coin_supply();
+		let too_low = TEST_BASE_UNIT / 10 - 1;
+		let too_high = 10 * TEST_BASE_UNIT + 1;
+
+		assert_ok!(Stablecoin::on_block_with_price(frequency, Some(too_low)));
+		assert_ok!(Stablecoin::on_block_with_price(2 * frequency, Some(too_high)));
+		assert_eq!(Stablecoin::coin_supply(), supply);
+		assert_eq!(
+			stablecoin_events(),
+			vec![
+				RawEvent::PriceOutOfBounds(frequency, too_low),
+				RawEvent::PriceOutOfBounds(2 * frequency, too_high),
+			]
+		);
+
+		// prices at the bounds are used
+		assert_ok!(Stablecoin::on_block_with_price(3 * frequency, Some(TEST_BASE_UNIT / 10)));
+		assert!(Stablecoin::coin_supply() > supply);
+	});
+}
+
+#[test]
+fn failed_adjustment_emits_event() {
+	use frame_support::traits::OnInitialize;
+
+	new_test_ext().execute_with(|| {
+		let frequency = AdjustmentFrequency::get();
+		System::set_block_number(frequency);
+		OraclePrice::set(0);
+
+		Stablecoin::on_initialize(frequency);
+		assert_eq!(
+			stablecoin_events(),
+			vec![
+				RawEvent::PriceObserved(frequency, 0),
+				RawEvent::AdjustmentFailed(frequency, DispatchError::from(Error::<Test>::ZeroPrice)),
+			]
+		);
+	})
+}
+
+#[test]
+fn simulate_adjustment_matches_expansion() {
+	new_test_ext().execute_with(|| {
+		let price = TEST_BASE_UNIT * 8 / 10;
+		let prev_supply = Stablecoin::coin_supply();
+		let simulated = Stablecoin::simulate_adjustment(price);
+		assert_eq!(Stablecoin::coin_supply(), prev_supply, "simulation should not change the supply");
+
+		assert_ok!(Stablecoin::expand_or_contract_on_price(price));
+		assert_eq!(
+			simulated,
+			Ok(SupplyAction::Expand(Stablecoin::coin_supply() - prev_supply))
+		);
+	});
+}
+
+#[test]
+fn adjustment_is_precise_for_large_supplies() {
+	new_test_ext().execute_with(|| {
+		let supply = Coins::max_value() / 2;
+		<CoinSupply>::put(supply);
+		// `supply * |base - price| / price` computed with integers
+		let expected = |price: Coins| {
+			let deviation = if price > TEST_BASE_UNIT {
+				price - TEST_BASE_UNIT
+			} else {
+				TEST_BASE_UNIT - price
+			};
+			let change = supply as u128 * deviation as u128 / min(price, TEST_BASE_UNIT) as u128;
+			change as Coins
+		};
+
+		for price in [TEST_BASE_UNIT - 1, TEST_BASE_UNIT * 2 / 3, TEST_BASE_UNIT / 2 + 7].iter() {
+			match Stablecoin::simulate_adjustment(*price) {
+				Ok(SupplyAction::Expand(amount)) => {
+					assert_le!(amount, expected(*price));
+					assert_le!(expected(*price) - amount, 1);
+				}
+				other => panic!("expected an expansion for price {}, got {:?}", price, other),
+			}
+		}
+		for price in [TEST_BASE_UNIT + 1, TEST_BASE_UNIT * 3 / 2 + 3].iter() {
+			match Stablecoin::simulate_adjustment(*price) {
+				Ok(SupplyAction::Contract(amount)) => {
+					assert_le!(amount, expected(*price));
+					assert_le!(expected(*price) - amount, 1);
+				}
+				other => panic!("expected a contraction for price {}, got {:?}", price, other),
+			}
+		}
+	});
+}
+
+#[test]
+fn simulate_adjustment_matches_contraction() {
+	new_test_ext().execute_with(|| {
+		let price = TEST_BASE_UNIT * 11 / 10;
+		for acc in 1..=5 {
+			assert_ok!(Stablecoin::bid_for_bond(
+				Origin::signed(acc),
+				Perbill::from_percent(80),
+				5 * BaseUnit::get()
+			));
+		}
+		let prev_supply = Stablecoin::coin_supply();
+		let bids_before = Stablecoin::bond_bids().len();
+		let simulated = Stablecoin::simulate_adjustment(price);
+		assert_eq!(Stablecoin::bond_bids().len(), bids_before, "simulation should not convert bids");
+
+		assert_ok!(Stablecoin::expand_or_contract_on_price(price));
+		assert_eq!(
+			simulated,
+			Ok(SupplyAction::Contract(prev_supply - Stablecoin::coin_supply()))
+		);
+	});
+}
+
+#[test]
+fn simulate_adjustment_at_peg_and_zero_price() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Stablecoin::simulate_adjustment(TEST_BASE_UNIT), Ok(SupplyAction::None));
+		assert_eq!(
+			Stablecoin::simulate_adjustment(0),
+			Err(DispatchError::from(Error::<Test>::ZeroPrice))
+		);
+	});
+}
+
+#[test]
+fn asymmetric_adjustment_factors() {
+	// prices deviating by the same fraction above and below the peg
+	let above = TEST_BASE_UNIT * 5 / 4;
+	let below = TEST_BASE_UNIT * 4 / 5;
+	new_test_ext().execute_with(|| {
+		let expansion = Stablecoin::simulate_adjustment(below);
+		let contraction = Stablecoin::simulate_adjustment(above);
+		assert_eq!(expansion, Ok(SupplyAction::Expand(InitialSupply::get() / 4)));
+		assert_eq!(contraction, Ok(SupplyAction::Contract(InitialSupply::get() / 4)));
+	});
+
+	ContractionFactor::set(Perbill::from_percent(80));
+	new_test_ext().execute_with(|| {
+		for acc in 1..=10 {
+			assert_ok!(Stablecoin::bid_for_bond(
+				Origin::signed(acc),
+				Perbill::from_percent(90),
+				5 * BaseUnit::get()
+			));
+		}
+		let prev_supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_or_contract_on_price(above));
+		let burned = prev_supply - Stablecoin::coin_supply();
+		assert_eq!(burned, InitialSupply::get() / 4 * 8 / 10);
+		assert_eq!(
+			Stablecoin::simulate_adjustment(below),
+			Ok(SupplyAction::Expand(Stablecoin::coin_supply() / 4))
+		);
+	});
+
+	ContractionFactor::set(Perbill::from_percent(100));
+	ExpansionFactor::set(Perbill::from_percent(50));
+	new_test_ext().execute_with(|| {
+		let prev_supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_or_contract_on_price(below));
+		assert_eq!(Stablecoin::coin_supply() - prev_supply, InitialSupply::get() / 8);
+	});
+}
+
+#[test]
+fn adjustment_damping_scales_supply_change() {
+	let price = TEST_BASE_UNIT * 8 / 10;
+	let full_change = new_test_ext().execute_with(|| {
+		let prev_supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_or_contract_on_price(price));
+		Stablecoin::coin_supply() - prev_supply
+	});
+	assert_eq!(full_change, InitialSupply::get() / 4);
+
+	AdjustmentDamping::set(Perbill::from_percent(50));
+	new_test_ext().execute_with(|| {
+		let prev_supply = Stablecoin::coin_supply();
+		assert_ok!(Stablecoin::expand_or_contract_on_price(price));
+		assert_eq!(Stablecoin::coin_supply() - prev_supply, full_change / 2);
+	});
+}
+
+#[test]
+fn peg_tolerance_prevents_small_adjustments() {
+	PegTolerance::set(Perbill::from_percent(1));
+	new_test_ext().execute_with(|| {
+		let prev_supply = Stablecoin::coin_supply();
+		let below = TEST_BASE_UNIT - TEST_BASE_UNIT / 200;
+		let above = TEST_BASE_UNIT + TEST_BASE_UNIT / 200;
+		assert_eq!(Stablecoin::deviation_of(below), Ok(PriceDeviation::AtPeg));
+		assert_eq!(Stablecoin::deviation_of(above), Ok(PriceDeviation::AtPeg));
+
+		assert_ok!(Stablecoin::expand_or_contract_on_price(below));
+		assert_ok!(Stablecoin::expand_or_contract_on_price(above));
+		assert_eq!(Stablecoin::coin_supply(), prev_supply);
+
+		// prices outside of the tolerance are still adjusted for
+		assert_ok!(Stablecoin::expand_or_contract_on_price(TEST_BASE_UNIT * 9 / 10));
+		assert_gt!(Stablecoin::coin_supply(), prev_supply);
+	});
+}
+
+#[test]
+fn price_deviation_test() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Stablecoin::deviation_of(TEST_BASE_UNIT), Ok(PriceDeviation::AtPeg));
+		assert_eq!(
+			Stablecoin::deviation_of(TEST_BASE_UNIT * 5 / 4),
+			Ok(PriceDeviation::Above(Fraction::from_num(0.25)))
+		);
+		assert_eq!(
+			Stablecoin::deviation_of(TEST_BASE_UNIT * 4 / 5),
+			Ok(PriceDeviation::Below(Fraction::from_num(0.25)))
+		);
+		assert_eq!(
+			Stablecoin::deviation_of(0),
+			Err(DispatchError::from(Error::<Test>::ZeroPrice))
+		);
+
+		assert_eq!(
+			Stablecoin::price_deviation(),
+			Err(DispatchError::from(Error::<Test>::NoPriceObserved))
+		);
+		// the price is recorded on every block, not only when adjusting the supply
+		let price = TEST_BASE_UNIT * 5 / 4;
+		assert_ok!(Stablecoin::on_block_with_price(1, Some(price)));
+		assert_eq!(
+			Stablecoin::price_deviation(),
+			Ok((price, PriceDeviation::Above(Fraction::from_num(0.25))))
+		);
+
+		// stale prices are not recorded
+		assert_ok!(Stablecoin::on_block_with_price(2, None));
+		assert_eq!(Stablecoin::last_observed_price(), Some(price));
+	});
+}
+
 #[test]
 fn supply_change_calculation() {
 	let price = TEST_BASE_UNIT + 100;
 	let supply = u64::max_value();
-	let contract_by = Stablecoin::calculate_supply_change(price, TEST_BASE_UNIT, supply);
+	let contract_by = Stablecoin::calculate_supply_change(price, TEST_BASE_UNIT, supply)
+		.expect("supply change should not overflow");
 	// the error should be low enough
 	assert_ge!(contract_by, u64::max_value() / 10 - 1);
 	assert_le!(contract_by, u64::max_value() / 10 + 1);
 }
+
+#[test]
+fn expansion_is_capped_near_max_supply() {
+	new_test_ext().execute_with(|| {
+		let headroom = 1000;
+		<CoinSupply>::put(u64::max_value() - headroom);
+		// a price of 1 would expand the supply by 999 times its size
+		assert_eq!(Stablecoin::simulate_adjustment(1), Ok(SupplyAction::Expand(headroom)));
+		assert_ok!(Stablecoin::expand_or_contract_on_price(1));
+		assert_eq!(Stablecoin::coin_supply(), u64::max_value());
+
+		// no room left to expand
+		assert_eq!(Stablecoin::simulate_adjustment(1), Ok(SupplyAction::None));
+	});
+}
+
+#[test]
+fn extreme_price_fails_cleanly() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Stablecoin::expand_or_contract_on_price(u64::max_value()),
+			Error::<Test>::GenericOverflow
+		);
+		assert_eq!(
+			Stablecoin::calculate_supply_change(u64::max_value(), TEST_BASE_UNIT, Stablecoin::coin_supply()),
+			Err(DispatchError::from(Error::<Test>::GenericOverflow))
+		);
+	});
+}
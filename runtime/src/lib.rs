@@ -287,24 +287,65 @@ impl price::Trait for Runtime {
 
 parameter_types! {
 	pub const ExpirationPeriod: BlockNumber = 100;
+	pub const BidExpirationPeriod: BlockNumber = 30 * DAYS;
 	pub const MaximumBids: u64 = 1_000;
+	pub const MaxTransfersPerBatch: u32 = 100;
+	pub const MaxBidsPerAccount: u64 = 100;
+	pub const MaxBidActionsPerBlock: u32 = 10;
+	pub const EvictionPolicy: stablecoin::BidEvictionPolicy = stablecoin::BidEvictionPolicy::EvictLowest;
+	pub const MaximumBonds: stablecoin::BondIndex = 10_000;
+	pub const PayoutMode: stablecoin::BondPayoutMode = stablecoin::BondPayoutMode::Fifo;
+	pub const MaxBondPayoutPerExpansion: Coins = Coins::max_value();
 	pub const AdjustmentFrequency: BlockNumber = 2;
+	pub const AdjustmentDamping: Perbill = Perbill::from_percent(100);
+	pub const PegTolerance: Perbill = Perbill::zero();
+	pub const ExpansionFactor: Perbill = Perbill::from_percent(100);
+	pub const ContractionFactor: Perbill = Perbill::from_percent(100);
 	pub const BaseUnit: Coins = 1_000_000;
+	pub const MinimumBidQuantity: Coins = BaseUnit::get();
+	pub const MinPriceBound: Coins = BaseUnit::get() / 100;
+	pub const MaxPriceBound: Coins = 100 * BaseUnit::get();
+	pub const Symbol: &'static [u8] = b"COIN";
 	pub const InitialSupply: Coins = 1000 * BaseUnit::get();
 	pub const MinimumSupply: Coins = BaseUnit::get();
 	pub const MinimumBondPrice: Perbill = Perbill::from_percent(10);
+	pub const BondBonus: Perbill = Perbill::zero();
+	pub const MaximumBondExpirationsPerBlock: u32 = 100;
+	pub const CoinExistentialDeposit: Coins = 0;
 }
 
 impl stablecoin::Trait for Runtime {
 	type Event = Event;
 
 	type ExpirationPeriod = ExpirationPeriod;
+	type BondExpiration = stablecoin::FixedExpiration<ExpirationPeriod>;
+	type BidExpirationPeriod = BidExpirationPeriod;
+	type AdminOrigin = system::EnsureRoot<AccountId>;
 	type MaximumBids = MaximumBids;
+	type MaxTransfersPerBatch = MaxTransfersPerBatch;
+	type MaxBidsPerAccount = MaxBidsPerAccount;
+	type MaxBidActionsPerBlock = MaxBidActionsPerBlock;
+	type BidEvictionPolicy = EvictionPolicy;
+	type MaximumBonds = MaximumBonds;
+	type BondPayoutMode = PayoutMode;
+	type MaxBondPayoutPerExpansion = MaxBondPayoutPerExpansion;
 	type AdjustmentFrequency = AdjustmentFrequency;
+	type AdjustmentDamping = AdjustmentDamping;
+	type PegTolerance = PegTolerance;
+	type ExpansionFactor = ExpansionFactor;
+	type ContractionFactor = ContractionFactor;
 	type BaseUnit = BaseUnit;
+	type MinimumBidQuantity = MinimumBidQuantity;
+	type MinPriceBound = MinPriceBound;
+	type MaxPriceBound = MaxPriceBound;
+	type Symbol = Symbol;
 	type InitialSupply = InitialSupply;
 	type MinimumSupply = MinimumSupply;
 	type MinimumBondPrice = MinimumBondPrice;
+	type BondBonus = BondBonus;
+	type MaximumBondExpirationsPerBlock = MaximumBondExpirationsPerBlock;
+	type ExistentialDeposit = CoinExistentialDeposit;
+	type OnSupplyChange = ();
 
 	type CoinPrice = price::Module<Runtime>;
 }
@@ -9,6 +9,8 @@
 //! 
 //! The queue eagerly inserts and removes values from its underlying storage map
 //! but lazily stores the bounds on `drop` or (explicit calls to) `commit`.
+//! A queue created with `new_readonly` does not store its bounds on `drop`
+//! and can be used to inspect the queue without writing to storage.
 //!
 //! Usage Example:
 //! ```rust,ignore
@@ -45,6 +47,7 @@ where
 {
 	start: Index,
 	length: Index,
+	read_only: bool,
 	_phantom: PhantomData<(Item, B, M)>,
 }
 
@@ -64,6 +67,24 @@ where
 		BoundedDeque {
 			start,
 			length,
+			read_only: false,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Create a new `BoundedDeque` based on the storage types that does not commit on `drop`.
+	///
+	/// Initializes itself from the `Bounds` storage like `new`, but leaves the bounds in storage
+	/// unchanged when dropped. Meant for inspecting the queue (e.g., with `peek_front` or `len`).
+	///
+	/// Note: Pushing and popping still inserts and removes items in the storage map eagerly,
+	/// so mutating a read-only queue leaves the storage in an inconsistent state.
+	pub fn new_readonly() -> BoundedDeque<Item, B, M, Index> {
+		let (start, length) = B::get();
+		BoundedDeque {
+			start,
+			length,
+			read_only: true,
 			_phantom: PhantomData,
 		}
 	}
@@ -75,6 +96,7 @@ where
 		BoundedDeque {
 			start,
 			length,
+			read_only: false,
 			_phantom: PhantomData,
 		}
 	}
@@ -84,34 +106,37 @@ where
 		self.start.wrapping_add(&self.length)
 	}
 
-	/// Push an item onto the back of the queue.
+	/// Push an item onto the back of the queue and return the index it was stored at.
 	///
-	/// + Will write over the item at the front if the queue is full.
+	/// + Will drop the item at the front (removing it from storage) if the queue is full.
 	/// + Will insert the new item into storage, but will not update the bounds in storage.
-	pub fn push_back(&mut self, item: Item) {
+	pub fn push_back(&mut self, item: Item) -> Index {
 		let index = self.end();
 		M::insert(index, item);
 		// this will intentionally overflow and wrap around when the end
 		// reaches `Index::max_value` because we want a ringbuffer.
 		let new_end = index.wrapping_add(&Index::from(1));
 		if new_end == self.start {
-			// queue is full and thus writing over the front item
+			// queue is full and thus dropping the front item
+			M::remove(self.start);
 			self.start = self.start.wrapping_add(&Index::from(1));
 		}
 		// simulate saturating add
 		self.length = Index::max(self.length, self.length.wrapping_add(&Index::from(1)));
+		index
 	}
 
-	/// Push an item onto the front of the queue.
+	/// Push an item onto the front of the queue and return the index it was stored at.
 	/// 
 	/// + Will write over the item at the back if the queue is full.
 	/// + Will insert the new item into storage, but will not update the bounds in storage.
-	pub fn push_front(&mut self, item: Item) {
+	pub fn push_front(&mut self, item: Item) -> Index {
 		let index = self.start.wrapping_sub(&Index::from(1));
 		M::insert(index, item);
 		self.start = index;
 		// simulate saturating add
 		self.length = Index::max(self.length, self.length.wrapping_add(&Index::from(1)));
+		index
 	}
 
 	/// Pop an item from the back of the queue.
@@ -141,6 +166,32 @@ where
 		item.into()
 	}
 
+	/// Return the item at the front of the queue without removing it.
+	///
+	/// Reads the item from storage, so changes to the returned item are not persisted.
+	pub fn peek_front(&self) -> Option<Item> {
+		if self.is_empty() {
+			return None;
+		}
+		Some(M::get(self.start))
+	}
+
+	/// Return an iterator over the items from front to back.
+	///
+	/// Reads the items from storage without changing the queue.
+	pub fn iter(&self) -> Iter<Item, M, Index> {
+		Iter {
+			next: self.start,
+			remaining: self.length,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Return the number of items in the queue.
+	pub fn len(&self) -> Index {
+		self.length
+	}
+
 	/// Return whether to consider the queue empty.
 	pub fn is_empty(&self) -> bool {
 		self.length == Index::from(0)
@@ -154,6 +205,38 @@ where
 	}
 }
 
+/// Iterator over the items of a `BoundedDeque` from front to back, see `BoundedDeque::iter`.
+pub struct Iter<Item, M, Index = DefaultIdx>
+where
+	Item: FullCodec,
+	M: StorageMap<Index, Item, Query = Item>,
+	Index: FullCodec + Eq + Ord + WrappingAdd + WrappingSub + From<u8> + Copy,
+{
+	next: Index,
+	remaining: Index,
+	_phantom: PhantomData<(Item, M)>,
+}
+
+impl<Item, M, Index> Iterator for Iter<Item, M, Index>
+where
+	Item: FullCodec,
+	M: StorageMap<Index, Item, Query = Item>,
+	Index: FullCodec + Eq + Ord + WrappingAdd + WrappingSub + From<u8> + Copy,
+{
+	type Item = Item;
+
+	fn next(&mut self) -> Option<Item> {
+		if self.remaining == Index::from(0) {
+			return None;
+		}
+		let item = M::get(self.next);
+		// wraps around at the end of the index range like the queue itself
+		self.next = self.next.wrapping_add(&Index::from(1));
+		self.remaining = self.remaining - Index::from(1);
+		Some(item)
+	}
+}
+
 impl<Item, B, M, Index> Drop for BoundedDeque<Item, B, M, Index>
 where
 	Item: FullCodec,
@@ -161,9 +244,11 @@ where
 	M: StorageMap<Index, Item, Query = Item>,
 	Index: FullCodec + Eq + Ord + WrappingAdd + WrappingSub + From<u8> + Copy,
 {
-	/// Commit on `drop`.
+	/// Commit on `drop` (unless created with `new_readonly`).
 	fn drop(&mut self) {
-		self.commit();
+		if !self.read_only {
+			self.commit();
+		}
 	}
 }
 
@@ -285,7 +370,7 @@ mod tests {
 	fn simple_push() {
 		new_test_ext().execute_with(|| {
 			let mut queue = Queue::new();
-			queue.push_back(SomeStruct { foo: 1, bar: 2 });
+			assert_eq!(queue.push_back(SomeStruct { foo: 1, bar: 2 }), 0);
 			queue.commit();
 			let (start, length) = TestModule::get_test_range();
 			assert_eq!((start, length), (0, 1));
@@ -308,6 +393,34 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn readonly_does_not_commit() {
+		new_test_ext().execute_with(|| {
+			<TestRange>::put((5, 0));
+			{
+				let mut queue = Queue::new();
+				queue.push_back(SomeStruct { foo: 1, bar: 2 });
+				queue.push_back(SomeStruct { foo: 3, bar: 4 });
+			}
+			assert_eq!(TestModule::get_test_range(), (5, 2));
+			{
+				let queue = Queue::new_readonly();
+				assert_eq!((queue.start, queue.length), (5, 2));
+				assert_eq!(queue.peek_front(), Some(SomeStruct { foo: 1, bar: 2 }));
+				assert_eq!(queue.len(), 2);
+			}
+			assert_eq!(TestModule::get_test_range(), (5, 2));
+			{
+				// not committed even if the bounds were changed
+				let mut queue = Queue::new_readonly();
+				queue.start = 6;
+				queue.length = 1;
+			}
+			assert_eq!(TestModule::get_test_range(), (5, 2));
+			assert_eq!(TestModule::get_test_value(6), SomeStruct { foo: 3, bar: 4 });
+		})
+	}
+
 	#[test]
 	fn simple_pop() {
 		new_test_ext().execute_with(|| {
@@ -327,6 +440,53 @@ mod tests {
 			queue.commit();
 			let (start, length) = TestModule::get_test_range();
 			assert_eq!((start, length), (1, 1));
+			assert_eq!(queue.len(), 1);
+		})
+	}
+
+	#[test]
+	fn peek_front() {
+		new_test_ext().execute_with(|| {
+			let mut queue = Queue::new();
+			assert_eq!(queue.peek_front(), None);
+			queue.push_back(SomeStruct { foo: 1, bar: 2 });
+			queue.push_back(SomeStruct { foo: 3, bar: 4 });
+
+			assert_eq!(queue.peek_front(), Some(SomeStruct { foo: 1, bar: 2 }));
+			assert_eq!(queue.peek_front(), Some(SomeStruct { foo: 1, bar: 2 }));
+			assert_eq!(queue.len(), 2);
+			queue.commit();
+			assert_eq!(TestModule::get_test_range(), (0, 2));
+
+			queue.pop_front();
+			assert_eq!(queue.peek_front(), Some(SomeStruct { foo: 3, bar: 4 }));
+			queue.pop_front();
+			assert_eq!(queue.peek_front(), None);
+		})
+	}
+
+	#[test]
+	fn iter_in_queue_order() {
+		new_test_ext().execute_with(|| {
+			// start close to the end of the index range to wrap around
+			<TestRange>::put((TestIdx::max_value() - 1, 0));
+			let mut queue = Queue::new();
+			for i in 1..5 {
+				queue.push_back(SomeStruct { foo: 42, bar: i });
+			}
+			queue.push_front(SomeStruct { foo: 42, bar: 0 });
+			queue.commit();
+
+			let bars: Vec<u64> = queue.iter().map(|item| item.bar).collect();
+			assert_eq!(bars, vec![0, 1, 2, 3, 4]);
+			assert_eq!(queue.len(), 5);
+			assert_eq!(TestModule::get_test_range(), (TestIdx::max_value() - 2, 5));
+
+			queue.pop_front();
+			queue.pop_back();
+			let bars: Vec<u64> = queue.iter().map(|item| item.bar).collect();
+			assert_eq!(bars, vec![1, 2, 3]);
+			assert_eq!(Queue::new().iter().count(), 5, "bounds in storage are not committed yet");
 		})
 	}
 
@@ -345,6 +505,7 @@ mod tests {
 				(1, 255),
 				"range should be inverted because the index wrapped around"
 			);
+			assert!(!<TestMap>::contains_key(0), "the dropped front item should be removed");
 
 			let item = queue.pop_front();
 			queue.commit();
@@ -2,17 +2,22 @@
 //!
 //! This module provides an implementation for a bounded priority queue
 //! that abstracts over a `Vec` in storage.
-//! 
+//!
+//! The items are kept as a binary max-heap, so both `push` and `pop` are
+//! `O(log n)` instead of requiring a sorted insert into the `Vec`.
+//! Note that the backing `Vec` is thus stored in heap order, not sorted.
+//! Use `into_sorted_vec` if you need the items sorted.
+//!
 //! The priority queue is bounded to the value provided by the passed `MaxLength` type.
 //! If it reaches its maximum when being `push`ed into it will return the evicted item.
-//! 
+//!
 //! The queue lazily syncs to the underlying storage on `drop` or (explicit calls to)
 //! `commit`.
 //!
 //! Usage Example:
 //! ```rust,ignore
 //! use storage_adapters::BoundedPriorityQueue;
-//! 
+//!
 //! parameter_types! {
 //!     pub const MaximumLength: u64 = 42;
 //! }
@@ -59,37 +64,65 @@ where
 	/// Create a new `BoundedPriorityQueue`.
 	///
 	/// Initializes itself from storage with the `Storage` type.
+	///
+	/// Note: The items are (re-)heapified on load so that items stored in any
+	/// order (e.g., sorted by an older version) are handled correctly.
 	pub fn new() -> BoundedPriorityQueue<Item, Storage, MaxLength> {
 		let items = Storage::get();
-		BoundedPriorityQueue {
+		let mut queue = BoundedPriorityQueue {
 			items,
 			_phantom: PhantomData,
-		}
+		};
+		queue.heapify();
+		queue
 	}
 
 	/// Sort a new item into the queue according to its priority.
-	/// 
+	///
 	/// Will return the smallest (according to `Ord`) item if length increases
 	/// over `MaxLength` otherwise.
-	// TODO: This could be abused by an attacker kicking out other items with the same
-	//       value.
+	/// An item only evicts the smallest item if it is strictly greater, otherwise
+	/// the pushed item itself is returned. This prevents items from being kicked
+	/// out by items with the same value.
+	///
+	/// **Complexity:** `O(log n)` if the queue is not full, `O(n)` if an item
+	/// needs to be evicted.
 	pub fn push(&mut self, item: Item) -> Option<Item> {
-		let index = self
-			.items
-			.binary_search_by(|it| it.cmp(&item))
-			.unwrap_or_else(|i| i);
-		self.items.insert(index, item);
-		if self.items.len() as u64 > MaxLength::get() {
-			return Some(self.items.remove(0));
+		if (self.items.len() as u64) < MaxLength::get() {
+			self.items.push(item);
+			self.sift_up(self.items.len() - 1);
+			return None;
+		}
+		// The queue is full so we need to evict the smallest item.
+		let min_index = match self.min_index() {
+			Some(index) => index,
+			// `MaxLength` is zero so nothing fits.
+			None => return Some(item),
+		};
+		if item <= self.items[min_index] {
+			return Some(item);
 		}
-		None
+		let evicted = core::mem::replace(&mut self.items[min_index], item);
+		// The minimum is always a leaf and we replaced it with a bigger item,
+		// so we only need to restore the heap property upwards.
+		self.sift_up(min_index);
+		Some(evicted)
 	}
 
 	/// Pop the greatest item from the queue.
 	///
 	/// Returns `None` if the queue is empty.
+	///
+	/// **Complexity:** `O(log n)`
 	pub fn pop(&mut self) -> Option<Item> {
-		self.items.pop()
+		if self.items.is_empty() {
+			return None;
+		}
+		let last = self.items.len() - 1;
+		self.items.swap(0, last);
+		let item = self.items.pop();
+		self.sift_down(0);
+		item
 	}
 
 	/// Return whether the queue is empty.
@@ -97,10 +130,84 @@ where
 		self.items.is_empty()
 	}
 
+	/// Retain only the items for which `keep` returns `true`.
+	///
+	/// **Complexity:** `O(n)`
+	pub fn retain<F>(&mut self, keep: F)
+	where
+		F: FnMut(&Item) -> bool,
+	{
+		self.items.retain(keep);
+		self.heapify();
+	}
+
+	/// Consume the queue and return its items sorted from smallest to greatest.
+	///
+	/// Note: Does not commit the queue to storage.
+	pub fn into_sorted_vec(mut self) -> Vec<Item> {
+		let mut items = core::mem::replace(&mut self.items, Vec::new());
+		// prevent committing the now empty queue on `drop`
+		core::mem::forget(self);
+		items.sort();
+		items
+	}
+
 	/// Commit the potentially changed backing `Vec` to storage.
 	pub fn commit(&mut self) {
 		Storage::put(self.items.clone());
 	}
+
+	/// Restore the heap property for all items.
+	///
+	/// **Complexity:** `O(n)`
+	fn heapify(&mut self) {
+		let len = self.items.len();
+		for index in (0..len / 2).rev() {
+			self.sift_down(index);
+		}
+	}
+
+	/// Move the item at `index` up until its parent is not smaller than it.
+	fn sift_up(&mut self, mut index: usize) {
+		while index > 0 {
+			let parent = (index - 1) / 2;
+			if self.items[index] <= self.items[parent] {
+				break;
+			}
+			self.items.swap(index, parent);
+			index = parent;
+		}
+	}
+
+	/// Move the item at `index` down until none of its children are greater than it.
+	fn sift_down(&mut self, mut index: usize) {
+		let len = self.items.len();
+		loop {
+			let left = 2 * index + 1;
+			let right = left + 1;
+			let mut greatest = index;
+			if left < len && self.items[left] > self.items[greatest] {
+				greatest = left;
+			}
+			if right < len && self.items[right] > self.items[greatest] {
+				greatest = right;
+			}
+			if greatest == index {
+				break;
+			}
+			self.items.swap(index, greatest);
+			index = greatest;
+		}
+	}
+
+	/// Return the index of the smallest item.
+	///
+	/// The smallest item of a max-heap is always one of the leaves which
+	/// occupy the second half of the backing `Vec`.
+	fn min_index(&self) -> Option<usize> {
+		let len = self.items.len();
+		(len / 2..len).min_by(|a, b| self.items[*a].cmp(&self.items[*b]))
+	}
 }
 
 impl<Item, Storage, MaxLength> Drop for BoundedPriorityQueue<Item, Storage, MaxLength>
@@ -147,12 +254,18 @@ mod tests {
 		}
 	}
 
-	#[derive(Clone, PartialEq, Encode, Decode, Default, Debug, PartialOrd, Eq)]
+	#[derive(Clone, PartialEq, Encode, Decode, Default, Debug, Eq)]
 	pub struct SomeStruct {
 		foo: u64,
 		bar: u64,
 	}
 
+	impl PartialOrd for SomeStruct {
+		fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+			Some(self.cmp(other))
+		}
+	}
+
 	impl Ord for SomeStruct {
 		fn cmp(&self, other: &Self) -> Ordering {
 			self.foo.cmp(&other.foo)
@@ -254,4 +367,69 @@ mod tests {
 			assert_eq!(queue.push(SomeStruct{foo: 0, bar: 2}), Some(SomeStruct{foo: 0, bar: 2}));
 		})
 	}
+
+	#[test]
+	fn push_equal_to_smallest_is_rejected() {
+		new_test_ext().execute_with(|| {
+			let mut queue = Queue::new();
+			let bar = 42;
+			for i in 0..MaxLength::get() {
+				assert_eq!(queue.push(SomeStruct { foo: i, bar }), None);
+			}
+			assert_eq!(queue.push(SomeStruct { foo: 0, bar: 1 }), Some(SomeStruct { foo: 0, bar: 1 }));
+		})
+	}
+
+	#[test]
+	fn pop_after_eviction() {
+		new_test_ext().execute_with(|| {
+			let mut queue = Queue::new();
+			let bar = 42;
+			for i in (0..MaxLength::get()).rev() {
+				assert_eq!(queue.push(SomeStruct { foo: i, bar }), None);
+			}
+			assert_eq!(queue.push(SomeStruct { foo: 10, bar: 1 }), Some(SomeStruct { foo: 0, bar }));
+
+			let mut popped = Vec::new();
+			while let Some(item) = queue.pop() {
+				popped.push(item.foo);
+			}
+			let mut expected: Vec<u64> = (1..MaxLength::get()).collect();
+			expected.push(10);
+			expected.sort();
+			expected.reverse();
+			assert_eq!(popped, expected);
+		})
+	}
+
+	#[test]
+	fn retain_keeps_heap_order() {
+		new_test_ext().execute_with(|| {
+			let mut queue = Queue::new();
+			for foo in &[5, 3, 8, 1, 9, 2] {
+				queue.push(SomeStruct { foo: *foo, bar: 0 });
+			}
+			queue.retain(|item| item.foo % 2 == 1);
+
+			assert_eq!(queue.pop().map(|i| i.foo), Some(9));
+			assert_eq!(queue.pop().map(|i| i.foo), Some(5));
+			assert_eq!(queue.pop().map(|i| i.foo), Some(3));
+			assert_eq!(queue.pop().map(|i| i.foo), Some(1));
+			assert_eq!(queue.pop(), None);
+		})
+	}
+
+	#[test]
+	fn sorted_vec_from_storage() {
+		new_test_ext().execute_with(|| {
+			{
+				let mut queue = Queue::new();
+				for foo in &[5, 3, 8, 1] {
+					queue.push(SomeStruct { foo: *foo, bar: 0 });
+				}
+			}
+			let sorted: Vec<u64> = Queue::new().into_sorted_vec().into_iter().map(|i| i.foo).collect();
+			assert_eq!(sorted, vec![1, 3, 5, 8]);
+		})
+	}
 }